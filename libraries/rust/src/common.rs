@@ -1,6 +1,6 @@
-use anchor_lang::{prelude::Pubkey, AccountDeserialize};
+use anchor_lang::prelude::Pubkey;
 use async_trait::async_trait;
-use lookup_table_registry::RegistryAccount;
+use lookup_table_registry::RegistryPage;
 use solana_address_lookup_table_program_gateway::state::AddressLookupTable;
 use solana_client::{client_error::ClientError, nonblocking::rpc_client::RpcClient};
 use solana_sdk::{
@@ -8,7 +8,12 @@ use solana_sdk::{
     transaction::TransactionError,
 };
 
-use crate::{Entry, LOOKUP_TABLE_REGISTRY_ID};
+use crate::{
+    derive_named_registry_page_address, derive_public_registry_address, registry_name, Entry,
+};
+
+/// The maximum number of accounts that can be requested at once
+const MAX_MULTIPLE_ACCOUNTS: usize = 100;
 
 #[derive(Debug, Clone)]
 pub struct Registry {
@@ -18,29 +23,49 @@ pub struct Registry {
 }
 
 impl Registry {
+    /// Fetch the registry whose address is derived from the authority, or
+    /// one of its named registries.
     pub async fn fetch(
         rpc: &(impl AccountReader + ?Sized),
         authority: &Pubkey,
+        name: Option<&str>,
     ) -> LookupRegistryResult<Self> {
-        let registry_address =
-            Pubkey::find_program_address(&[authority.as_ref()], &LOOKUP_TABLE_REGISTRY_ID).0;
-        let registry = match rpc.get_account(&registry_address).await {
-            Ok(value) => value,
-            Err(e) => match e {
-                AccountReadError::AccountNotFound => {
-                    return Err(LookupRegistryError::RegistryNotFound(registry_address))
-                }
-                AccountReadError::Custom(e) => {
-                    return Err(LookupRegistryError::AccountReadError(e))
-                }
-            },
+        let name = match name {
+            Some(name) => registry_name(name).ok_or_else(|| {
+                LookupRegistryError::InvalidArgument(format!("Invalid registry name {name}"))
+            })?,
+            None => Default::default(),
         };
-        let registry = RegistryAccount::try_deserialize(&mut registry.data())?;
+        Self::fetch_address(
+            rpc,
+            &derive_named_registry_page_address(authority, &name, 0),
+        )
+        .await
+    }
+
+    /// Fetch the public registry of a program.
+    pub async fn fetch_public(
+        rpc: &(impl AccountReader + ?Sized),
+        program: &Pubkey,
+    ) -> LookupRegistryResult<Self> {
+        Self::fetch_address(rpc, &derive_public_registry_address(program)).await
+    }
+
+    /// Fetch a registry by the address of its first page, which is useful for
+    /// registries that have been transferred to another authority.
+    ///
+    /// The entries of all the pages of the registry are returned.
+    pub async fn fetch_address(
+        rpc: &(impl AccountReader + ?Sized),
+        registry_address: &Pubkey,
+    ) -> LookupRegistryResult<Self> {
+        let pages = Self::fetch_pages(rpc, registry_address).await?;
+        let registry = &pages[0];
 
         let mut pubkeys = vec![];
-        let addresses = registry
-            .tables
+        let addresses = pages
             .iter()
+            .flat_map(|page| page.tables.iter())
             .filter(|entry| {
                 if entry.discriminator > 1 {
                     pubkeys.push(entry.table);
@@ -51,31 +76,95 @@ impl Registry {
             })
             .collect::<Vec<_>>();
 
-        let accounts = rpc.get_multiple_accounts(&pubkeys).await.unwrap();
+        let accounts = get_multiple_accounts(rpc, &pubkeys).await?;
         let tables = accounts
             .into_iter()
             .zip(addresses)
             .filter_map(|(account, entry)| {
                 let Some(account) = account else {
-                return None;
-            };
+                    return None;
+                };
                 let Ok(table) = AddressLookupTable::deserialize(account.data()) else {
-                return None;
-            };
+                    return None;
+                };
                 Some(Entry {
                     discriminator: entry.discriminator,
                     lookup_address: entry.table,
+                    frozen: entry.is_frozen(),
+                    label: entry.label_string(),
+                    metadata: entry.metadata,
+                    created_slot: entry.created_slot,
                     addresses: table.addresses.iter().copied().collect(),
                 })
             })
             .collect();
 
         Ok(Self {
-            authority: *authority,
+            authority: registry.authority,
             version: registry.version,
             tables,
         })
     }
+
+    /// Fetch all the pages of a registry by the address of its first page.
+    pub async fn fetch_pages(
+        rpc: &(impl AccountReader + ?Sized),
+        registry_address: &Pubkey,
+    ) -> LookupRegistryResult<Vec<RegistryPage>> {
+        let registry = match rpc.get_account(registry_address).await {
+            Ok(value) => value,
+            Err(e) => match e {
+                AccountReadError::AccountNotFound => {
+                    return Err(LookupRegistryError::RegistryNotFound(*registry_address))
+                }
+                AccountReadError::Custom(e) => {
+                    return Err(LookupRegistryError::AccountReadError(e))
+                }
+            },
+        };
+        let registry = RegistryPage::try_deserialize_versioned(&mut registry.data())?;
+
+        let page_addresses = (1..registry.page_count)
+            .map(|page| {
+                derive_named_registry_page_address(&registry.seed_authority, &registry.name, page)
+            })
+            .collect::<Vec<_>>();
+        let accounts = get_multiple_accounts(rpc, &page_addresses).await?;
+        let mut pages = Vec::with_capacity(registry.page_count as usize);
+        pages.push(registry);
+        for (account, address) in accounts.into_iter().zip(page_addresses) {
+            let Some(account) = account else {
+                return Err(LookupRegistryError::RegistryNotFound(address));
+            };
+            pages.push(RegistryPage::try_deserialize_versioned(
+                &mut account.data(),
+            )?);
+        }
+
+        Ok(pages)
+    }
+}
+
+/// Get multiple accounts in batches of the maximum accounts allowed per request.
+async fn get_multiple_accounts(
+    rpc: &(impl AccountReader + ?Sized),
+    pubkeys: &[Pubkey],
+) -> LookupRegistryResult<Vec<Option<Account>>> {
+    let mut accounts = Vec::with_capacity(pubkeys.len());
+    for chunk in pubkeys.chunks(MAX_MULTIPLE_ACCOUNTS) {
+        let chunk = rpc
+            .get_multiple_accounts(chunk)
+            .await
+            .map_err(|e| match e {
+                AccountReadError::AccountNotFound => {
+                    LookupRegistryError::AccountReadError(anyhow::anyhow!("Account not found"))
+                }
+                AccountReadError::Custom(e) => LookupRegistryError::AccountReadError(e),
+            })?;
+        accounts.extend(chunk);
+    }
+
+    Ok(accounts)
 }
 
 #[derive(thiserror::Error, Debug)]
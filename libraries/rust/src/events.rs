@@ -0,0 +1,140 @@
+//! Decode the events that the registry program emits in transaction logs.
+
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use lookup_table_registry::{
+    AuthorityChanged, AuthorityProposed, DelegateAdded, DelegateRemoved, EntryLabelSet,
+    LookupTableClosed, LookupTableCreated, LookupTableDeactivated, LookupTableDetached,
+    LookupTableExtended, LookupTableFrozen, LookupTableImported, PublicRegistryInitialized,
+    RecipientSet, RegistryClosed, RegistryCompacted, RegistryInitialized, RegistryMigrated,
+    RegistryPageInitialized, RegistryRepaired, ID as LOOKUP_REGISTRY_ID,
+};
+
+/// The prefix of logs that contain event data
+const PROGRAM_DATA: &str = "Program data: ";
+
+macro_rules! registry_events {
+    ($($event:ident),* $(,)?) => {
+        /// An event emitted by the registry program
+        #[derive(Debug)]
+        pub enum RegistryEvent {
+            $($event($event),)*
+        }
+
+        impl RegistryEvent {
+            /// Decode an event from its data, which starts with the event's discriminator.
+            ///
+            /// Returns `None` if the data is not a registry event.
+            pub fn decode(data: &[u8]) -> Option<Self> {
+                if data.len() < 8 {
+                    return None;
+                }
+                let (discriminator, mut data) = data.split_at(8);
+                $(
+                    if discriminator == $event::DISCRIMINATOR {
+                        return $event::deserialize(&mut data).ok().map(Self::$event);
+                    }
+                )*
+                None
+            }
+        }
+    };
+}
+
+registry_events!(
+    RegistryInitialized,
+    PublicRegistryInitialized,
+    RegistryPageInitialized,
+    RegistryClosed,
+    RegistryMigrated,
+    RegistryCompacted,
+    RegistryRepaired,
+    LookupTableCreated,
+    LookupTableImported,
+    LookupTableExtended,
+    LookupTableDeactivated,
+    LookupTableClosed,
+    RecipientSet,
+    DelegateAdded,
+    DelegateRemoved,
+    LookupTableDetached,
+    LookupTableFrozen,
+    EntryLabelSet,
+    AuthorityProposed,
+    AuthorityChanged,
+);
+
+/// Parse the registry events from the logs of a transaction, in the order
+/// they were emitted.
+///
+/// Only data logged while the registry program is executing is decoded, so
+/// that other programs can't forge registry events.
+pub fn parse_logs<S: AsRef<str>>(logs: &[S]) -> Vec<RegistryEvent> {
+    let registry_id = LOOKUP_REGISTRY_ID.to_string();
+    let mut programs: Vec<&str> = vec![];
+    let mut events = vec![];
+    for log in logs {
+        let log = log.as_ref();
+        if let Some(data) = log.strip_prefix(PROGRAM_DATA) {
+            if programs.last() != Some(&registry_id.as_str()) {
+                continue;
+            }
+            if let Some(event) = base64::decode(data)
+                .ok()
+                .and_then(|data| RegistryEvent::decode(&data))
+            {
+                events.push(event);
+            }
+            continue;
+        }
+        let mut words = log.split(' ');
+        let (Some("Program"), Some(program), Some(status)) =
+            (words.next(), words.next(), words.next())
+        else {
+            continue;
+        };
+        if status == "invoke" {
+            programs.push(program);
+        } else if status == "success" || status == "failed:" {
+            programs.pop();
+        }
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use anchor_lang::{prelude::Pubkey, Event};
+
+    use super::*;
+
+    #[test]
+    fn test_parse_logs() {
+        let created = LookupTableCreated {
+            registry: Pubkey::new_unique(),
+            table: Pubkey::new_unique(),
+            discriminator: 2,
+            recent_slot: 100,
+        };
+        let data = format!("{PROGRAM_DATA}{}", base64::encode(created.data()));
+        let other_program = Pubkey::new_unique();
+        let logs = [
+            format!("Program {LOOKUP_REGISTRY_ID} invoke [1]"),
+            "Program log: Instruction: CreateLookupTable".to_string(),
+            format!("Program {other_program} invoke [2]"),
+            // Data logged by other programs is ignored
+            data.clone(),
+            format!("Program {other_program} success"),
+            data,
+            format!("Program {LOOKUP_REGISTRY_ID} success"),
+        ];
+
+        let events = parse_logs(&logs);
+        assert_eq!(events.len(), 1);
+        let RegistryEvent::LookupTableCreated(event) = &events[0] else {
+            panic!("Unexpected event {:?}", events[0]);
+        };
+        assert_eq!(event.table, created.table);
+        assert_eq!(event.recent_slot, created.recent_slot);
+    }
+}
@@ -2,30 +2,83 @@
 //! The instruction builder is useful if wanting to combine instructions,
 //! otherwise use [crate::registy::LookupRegistry].
 
-use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_lang::{prelude::AccountMeta, InstructionData, ToAccountMetas};
 use lookup_table_registry::{
-    accounts as ix_accounts, instruction as ix_data, ID as LOOKUP_REGISTRY_ID,
+    accounts as ix_accounts, instruction as ix_data, ENTRY_LABEL_LEN, ENTRY_METADATA_LEN,
+    ID as LOOKUP_REGISTRY_ID, REGISTRY_NAME_LEN,
 };
 use solana_address_lookup_table_program_gateway::ID as LOOKUP_ID;
 use solana_sdk::{
-    instruction::Instruction, pubkey::Pubkey, system_program::ID as SYSTEM_PROGAM_ID,
+    bpf_loader_upgradeable, instruction::Instruction, pubkey::Pubkey,
+    system_program::ID as SYSTEM_PROGAM_ID,
 };
 
+use crate::{derive_named_registry_page_address, derive_public_registry_address};
+
 /// An instruction builder of the lookup table registry program.
+#[derive(Clone)]
 pub struct InstructionBuilder {
     /// The authority that owns the lookup table
     pub authority: Pubkey,
     /// The payer of transaction costs and rent
     pub payer: Pubkey,
+    /// The authority that the registry is derived from, which is the authority
+    /// unless the registry was transferred from another authority
+    pub seed_authority: Pubkey,
+    /// The page of the registry that lookup table instructions use
+    pub page: u8,
+    /// The program of a public registry, which is used instead of the
+    /// authority's registry if set
+    pub program: Option<Pubkey>,
+    /// The name of the registry, if it's one of the named registries of the
+    /// seed authority, see [crate::registry_name]
+    pub name: Option<[u8; REGISTRY_NAME_LEN]>,
 }
 
 impl InstructionBuilder {
     /// Creates a new instruction builder
     pub fn new(authority: Pubkey, payer: Pubkey) -> Self {
-        Self { authority, payer }
+        Self {
+            authority,
+            payer,
+            seed_authority: authority,
+            page: 0,
+            program: None,
+            name: None,
+        }
+    }
+
+    /// Use a registry that was transferred to the authority.
+    ///
+    /// A registry keeps the address derived from its original authority.
+    pub fn with_seed_authority(mut self, seed_authority: Pubkey) -> Self {
+        self.seed_authority = seed_authority;
+        self
     }
 
-    /// Creates an instruction to initialize a lookup table registry.
+    /// Use a page of the registry for lookup table instructions.
+    pub fn with_page(mut self, page: u8) -> Self {
+        self.page = page;
+        self
+    }
+
+    /// Use the public registry of a program, in which the authority can extend
+    /// lookup tables. Only its curator can create and manage its tables otherwise,
+    /// unless the registry has no curator, in which case anyone can create them.
+    pub fn with_public_registry(mut self, program: Pubkey) -> Self {
+        self.program = Some(program);
+        self
+    }
+
+    /// Use a named registry of the seed authority, which is separate from the
+    /// registry derived from the seed authority alone.
+    pub fn with_name(mut self, name: [u8; REGISTRY_NAME_LEN]) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Creates an instruction to initialize a lookup table registry, which is
+    /// a named registry if the builder has a name.
     pub fn init_registry(&self) -> Instruction {
         let accounts = ix_accounts::InitRegistryAccount {
             authority: self.authority,
@@ -38,30 +91,55 @@ impl InstructionBuilder {
         Instruction {
             program_id: LOOKUP_REGISTRY_ID,
             accounts,
-            data: ix_data::InitRegistryAccount {}.data(),
+            data: ix_data::InitRegistryAccount { name: self.name }.data(),
+        }
+    }
+
+    /// Creates an instruction to initialize the public registry of the builder's
+    /// program, which has to be set with [Self::with_public_registry].
+    ///
+    /// The builder's authority has to be the upgrade authority of the program.
+    /// The curator has to be the upgrade authority too, or the default pubkey
+    /// for a registry without a curator.
+    pub fn init_public_registry(&self, curator: Pubkey) -> Instruction {
+        let program = self
+            .program
+            .expect("the builder does not use a public registry");
+        let (program_data, _) =
+            Pubkey::find_program_address(&[program.as_ref()], &bpf_loader_upgradeable::ID);
+        let accounts = ix_accounts::InitPublicRegistry {
+            payer: self.payer,
+            upgrade_authority: self.authority,
+            program,
+            program_data,
+            registry_account: self.registry_address(),
+            system_program: SYSTEM_PROGAM_ID,
+        }
+        .to_account_metas(None);
+
+        Instruction {
+            program_id: LOOKUP_REGISTRY_ID,
+            accounts,
+            data: ix_data::InitPublicRegistry { curator }.data(),
         }
     }
 
     /// Instruction to create a lookup table.
     ///
+    /// The discriminator tags the table by its purpose, and has to be greater
+    /// than [lookup_table_registry::discriminator::DEACTIVATED].
+    ///
     /// Returns the address of the lookup table with the instruction to create it.
     pub fn create_lookup_table(
         &self,
         recent_slot: u64,
-        // Not required, kept for future compat purposes
-        _discriminator: u64,
+        discriminator: u64,
     ) -> (Instruction, Pubkey) {
-        // Get slot
-        let lookup_table =
-            solana_address_lookup_table_program_gateway::instruction::derive_lookup_table_address(
-                &self.authority,
-                recent_slot,
-            )
-            .0;
+        let lookup_table = crate::derive_lookup_table_address(&self.page_address(), recent_slot);
         let accounts = ix_accounts::CreateLookupTable {
             authority: self.authority,
             payer: self.payer,
-            registry_account: self.registry_address(),
+            registry_account: self.page_address(),
             lookup_table,
             address_lookup_table_program: LOOKUP_ID,
             system_program: SYSTEM_PROGAM_ID,
@@ -74,7 +152,7 @@ impl InstructionBuilder {
                 accounts,
                 data: ix_data::CreateLookupTable {
                     recent_slot,
-                    _discriminator: 0,
+                    discriminator,
                 }
                 .data(),
             },
@@ -82,12 +160,114 @@ impl InstructionBuilder {
         )
     }
 
+    /// Instruction to create a lookup table and add addresses to it.
+    ///
+    /// The number of addresses is limited by the size of the transaction.
+    ///
+    /// Returns the address of the lookup table with the instruction to create it.
+    pub fn create_and_extend_lookup_table(
+        &self,
+        recent_slot: u64,
+        discriminator: u64,
+        addresses: &[Pubkey],
+    ) -> (Instruction, Pubkey) {
+        let lookup_table = crate::derive_lookup_table_address(&self.page_address(), recent_slot);
+        let accounts = ix_accounts::CreateLookupTable {
+            authority: self.authority,
+            payer: self.payer,
+            registry_account: self.page_address(),
+            lookup_table,
+            address_lookup_table_program: LOOKUP_ID,
+            system_program: SYSTEM_PROGAM_ID,
+        }
+        .to_account_metas(None);
+
+        (
+            Instruction {
+                program_id: LOOKUP_REGISTRY_ID,
+                accounts,
+                data: ix_data::CreateAndExtendLookupTable {
+                    recent_slot,
+                    discriminator,
+                    addresses: addresses.to_vec(),
+                }
+                .data(),
+            },
+            lookup_table,
+        )
+    }
+
+    /// Instruction to create a lookup table for each of the recent slots, which
+    /// have to be in ascending order.
+    ///
+    /// Up to [lookup_table_registry::MAX_CREATE_LOOKUP_TABLES] lookup tables can be created at once.
+    ///
+    /// Returns the addresses of the lookup tables with the instruction to create them.
+    pub fn create_lookup_tables(
+        &self,
+        recent_slots: &[u64],
+        discriminator: u64,
+    ) -> (Instruction, Vec<Pubkey>) {
+        let lookup_tables = recent_slots
+            .iter()
+            .map(|slot| crate::derive_lookup_table_address(&self.page_address(), *slot))
+            .collect::<Vec<_>>();
+        let mut accounts = ix_accounts::CreateLookupTables {
+            authority: self.authority,
+            payer: self.payer,
+            registry_account: self.page_address(),
+            address_lookup_table_program: LOOKUP_ID,
+            system_program: SYSTEM_PROGAM_ID,
+        }
+        .to_account_metas(None);
+        accounts.extend(
+            lookup_tables
+                .iter()
+                .map(|table| AccountMeta::new(*table, false)),
+        );
+
+        (
+            Instruction {
+                program_id: LOOKUP_REGISTRY_ID,
+                accounts,
+                data: ix_data::CreateLookupTables {
+                    recent_slots: recent_slots.to_vec(),
+                    discriminator,
+                }
+                .data(),
+            },
+            lookup_tables,
+        )
+    }
+
+    /// Creates an instruction to import a lookup table that was created outside
+    /// of the registry into the builder's page.
+    pub fn import_lookup_table(&self, lookup_table: Pubkey, discriminator: u64) -> Instruction {
+        let accounts = ix_accounts::ImportLookupTable {
+            authority: self.authority,
+            payer: self.payer,
+            registry_account: self.page_address(),
+            lookup_table,
+            system_program: SYSTEM_PROGAM_ID,
+        }
+        .to_account_metas(None);
+
+        Instruction {
+            program_id: LOOKUP_REGISTRY_ID,
+            accounts,
+            data: ix_data::ImportLookupTable { discriminator }.data(),
+        }
+    }
+
     /// Creates an instruction to remove a lookup table.
-    pub fn remove_lookup_table(&self, lookup_table: Pubkey) -> Instruction {
+    ///
+    /// The recipient of the lamports of a closed table doesn't have to sign,
+    /// but has to be the recipient of the registry page if it has one.
+    pub fn remove_lookup_table(&self, lookup_table: Pubkey, recipient: Pubkey) -> Instruction {
         let accounts = ix_accounts::RemoveLookupTable {
             authority: self.authority,
-            recipient: self.payer,
-            registry_account: self.registry_address(),
+            recipient,
+            registry_account: self.page_address(),
             lookup_table,
             address_lookup_table_program: LOOKUP_ID,
             system_program: SYSTEM_PROGAM_ID,
@@ -101,41 +281,393 @@ impl InstructionBuilder {
         }
     }
 
+    /// Creates an instruction for anyone to close a deactivated lookup table,
+    /// returning its lamports to the recipient of the registry page.
+    pub fn close_lookup_table(&self, lookup_table: Pubkey, recipient: Pubkey) -> Instruction {
+        let accounts = ix_accounts::CloseLookupTable {
+            recipient,
+            registry_account: self.page_address(),
+            lookup_table,
+            address_lookup_table_program: LOOKUP_ID,
+        }
+        .to_account_metas(None);
+
+        Instruction {
+            program_id: LOOKUP_REGISTRY_ID,
+            accounts,
+            data: ix_data::CloseLookupTable.data(),
+        }
+    }
+
+    /// Creates an instruction to set the recipient of the lookup tables of a
+    /// registry page that anyone can close.
+    pub fn set_recipient(&self, recipient: Pubkey) -> Instruction {
+        let accounts = ix_accounts::SetRecipient {
+            authority: self.authority,
+            registry_account: self.page_address(),
+        }
+        .to_account_metas(None);
+
+        Instruction {
+            program_id: LOOKUP_REGISTRY_ID,
+            accounts,
+            data: ix_data::SetRecipient { recipient }.data(),
+        }
+    }
+
+    /// Creates an instruction to allow a delegate to extend the lookup tables
+    /// of the builder's page with a discriminator.
+    pub fn add_delegate(&self, delegate: Pubkey, discriminator: u64) -> Instruction {
+        let accounts = ix_accounts::ManageDelegate {
+            authority: self.authority,
+            registry_account: self.page_address(),
+        }
+        .to_account_metas(None);
+
+        Instruction {
+            program_id: LOOKUP_REGISTRY_ID,
+            accounts,
+            data: ix_data::AddDelegate {
+                delegate,
+                discriminator,
+            }
+            .data(),
+        }
+    }
+
+    /// Creates an instruction to revoke a delegate's access to the lookup
+    /// tables of the builder's page with a discriminator.
+    pub fn remove_delegate(&self, delegate: Pubkey, discriminator: u64) -> Instruction {
+        let accounts = ix_accounts::ManageDelegate {
+            authority: self.authority,
+            registry_account: self.page_address(),
+        }
+        .to_account_metas(None);
+
+        Instruction {
+            program_id: LOOKUP_REGISTRY_ID,
+            accounts,
+            data: ix_data::RemoveDelegate {
+                delegate,
+                discriminator,
+            }
+            .data(),
+        }
+    }
+
     /// Creates an instruction to append addresses to a lookup table.
-    /// First inspects the lookup table to remove any duplicate addresses,
-    /// then appends the unique new addresses.
     ///
-    /// An error is returned if the addresses would exceed the lookup table's limit.
+    /// A delegate of the registry signs as the builder's authority, with the
+    /// registry's authority as the seed authority, see [InstructionBuilder::with_seed_authority].
+    ///
+    /// If `dedup_tables` is set, the program drops the addresses that are
+    /// already in the lookup table or in any of the other tables before appending
+    /// the rest, which can be none. The other tables have to be active tables
+    /// of the registry page.
+    ///
+    /// An error is returned if the addresses would exceed the lookup table's limit,
+    /// or if the discriminator does not match the one of the registry entry.
     pub fn append_to_lookup_table(
         &self,
         lookup_table: Pubkey,
         addresses: &[Pubkey],
-        // Not required, kept for future compat purposes
-        _discriminator: u64,
+        discriminator: u64,
+        dedup_tables: Option<&[Pubkey]>,
     ) -> Instruction {
-        let accounts = ix_accounts::AppendToLookupTable {
+        let mut accounts = ix_accounts::AppendToLookupTable {
             authority: self.authority,
             payer: self.payer,
-            registry_account: self.registry_address(),
+            registry_account: self.page_address(),
             lookup_table,
             address_lookup_table_program: LOOKUP_ID,
             system_program: SYSTEM_PROGAM_ID,
         }
         .to_account_metas(None);
+        accounts.extend(
+            dedup_tables
+                .unwrap_or_default()
+                .iter()
+                .map(|table| AccountMeta::new_readonly(*table, false)),
+        );
 
         Instruction {
             program_id: LOOKUP_REGISTRY_ID,
             accounts,
             data: ix_data::AppendToLookupTable {
-                _discriminator: 0,
                 addresses: addresses.to_vec(),
+                discriminator,
+                dedup: dedup_tables.is_some(),
             }
             .data(),
         }
     }
 
-    /// Derive the address of the registry account using the authority.
+    /// Creates an instruction to freeze a lookup table, after which it can
+    /// never be extended or removed.
+    pub fn freeze_lookup_table(&self, lookup_table: Pubkey) -> Instruction {
+        let accounts = ix_accounts::FreezeLookupTable {
+            authority: self.authority,
+            registry_account: self.page_address(),
+            lookup_table,
+            address_lookup_table_program: LOOKUP_ID,
+        }
+        .to_account_metas(None);
+
+        Instruction {
+            program_id: LOOKUP_REGISTRY_ID,
+            accounts,
+            data: ix_data::FreezeLookupTable.data(),
+        }
+    }
+
+    /// Creates an instruction to add a page to the registry.
+    ///
+    /// The page has to be the number of pages that the registry has.
+    pub fn init_registry_page(&self, page: u8) -> Instruction {
+        let accounts = ix_accounts::InitRegistryPage {
+            authority: self.authority,
+            payer: self.payer,
+            registry_account: self.registry_address(),
+            registry_page: self.registry_page_address(page),
+            system_program: SYSTEM_PROGAM_ID,
+        }
+        .to_account_metas(None);
+
+        Instruction {
+            program_id: LOOKUP_REGISTRY_ID,
+            accounts,
+            data: ix_data::InitRegistryPage.data(),
+        }
+    }
+
+    /// Creates an instruction to close the builder's page of the registry,
+    /// which has to be its last page.
+    ///
+    /// All the page's deactivated lookup tables have to be provided, and
+    /// are closed with the page.
+    pub fn close_registry_page(
+        &self,
+        deactivated_tables: &[Pubkey],
+        recipient: Pubkey,
+    ) -> Instruction {
+        let mut accounts = ix_accounts::CloseRegistryPage {
+            authority: self.authority,
+            recipient,
+            registry_account: self.registry_address(),
+            registry_page: self.page_address(),
+            address_lookup_table_program: LOOKUP_ID,
+            system_program: SYSTEM_PROGAM_ID,
+        }
+        .to_account_metas(None);
+        accounts.extend(
+            deactivated_tables
+                .iter()
+                .map(|table| AccountMeta::new(*table, false)),
+        );
+
+        Instruction {
+            program_id: LOOKUP_REGISTRY_ID,
+            accounts,
+            data: ix_data::CloseRegistryPage.data(),
+        }
+    }
+
+    /// Creates an instruction to close the registry account, returning its
+    /// lamports to the recipient.
+    ///
+    /// All the registry's deactivated lookup tables have to be provided, and
+    /// are closed with the registry. Further pages have to be closed first.
+    pub fn close_registry_account(
+        &self,
+        deactivated_tables: &[Pubkey],
+        recipient: Pubkey,
+    ) -> Instruction {
+        let mut accounts = ix_accounts::CloseRegistryAccount {
+            authority: self.authority,
+            recipient,
+            registry_account: self.registry_address(),
+            address_lookup_table_program: LOOKUP_ID,
+            system_program: SYSTEM_PROGAM_ID,
+        }
+        .to_account_metas(None);
+        accounts.extend(
+            deactivated_tables
+                .iter()
+                .map(|table| AccountMeta::new(*table, false)),
+        );
+
+        Instruction {
+            program_id: LOOKUP_REGISTRY_ID,
+            accounts,
+            data: ix_data::CloseRegistryAccount.data(),
+        }
+    }
+
+    /// Creates an instruction to remove a lookup table owned by the authority
+    /// from the registry without closing it.
+    pub fn detach_lookup_table(&self, lookup_table: Pubkey) -> Instruction {
+        let accounts = ix_accounts::DetachLookupTable {
+            authority: self.authority,
+            registry_account: self.page_address(),
+            lookup_table,
+        }
+        .to_account_metas(None);
+
+        Instruction {
+            program_id: LOOKUP_REGISTRY_ID,
+            accounts,
+            data: ix_data::DetachLookupTable.data(),
+        }
+    }
+
+    /// Creates an instruction to set the label and metadata of a lookup table.
+    pub fn set_entry_label(
+        &self,
+        lookup_table: Pubkey,
+        label: [u8; ENTRY_LABEL_LEN],
+        metadata: [u8; ENTRY_METADATA_LEN],
+    ) -> Instruction {
+        let accounts = ix_accounts::SetEntryLabel {
+            authority: self.authority,
+            registry_account: self.page_address(),
+            lookup_table,
+        }
+        .to_account_metas(None);
+
+        Instruction {
+            program_id: LOOKUP_REGISTRY_ID,
+            accounts,
+            data: ix_data::SetEntryLabel { label, metadata }.data(),
+        }
+    }
+
+    /// Creates an instruction to compact the builder's page of the registry,
+    /// refunding rent to the recipient.
+    ///
+    /// Live entries are moved to the front of the page if `reorder` is set.
+    pub fn compact_registry(&self, reorder: bool, recipient: Pubkey) -> Instruction {
+        let accounts = ix_accounts::CompactRegistry {
+            authority: self.authority,
+            recipient,
+            registry_account: self.page_address(),
+        }
+        .to_account_metas(None);
+
+        Instruction {
+            program_id: LOOKUP_REGISTRY_ID,
+            accounts,
+            data: ix_data::CompactRegistry { reorder }.data(),
+        }
+    }
+
+    /// Creates an instruction to recompute the counters of the builder's page
+    /// of the registry.
+    pub fn repair_registry(&self) -> Instruction {
+        let accounts = ix_accounts::RepairRegistry {
+            registry_account: self.page_address(),
+        }
+        .to_account_metas(None);
+
+        Instruction {
+            program_id: LOOKUP_REGISTRY_ID,
+            accounts,
+            data: ix_data::RepairRegistry.data(),
+        }
+    }
+
+    /// Creates an instruction to report the membership of a lookup table in
+    /// the builder's page of the registry, as a [lookup_table_registry::TableVerification]
+    /// in the return data of the transaction.
+    pub fn verify_table(&self, lookup_table: Pubkey) -> Instruction {
+        let accounts = ix_accounts::VerifyTable {
+            registry_account: self.page_address(),
+            lookup_table,
+        }
+        .to_account_metas(None);
+
+        Instruction {
+            program_id: LOOKUP_REGISTRY_ID,
+            accounts,
+            data: ix_data::VerifyTable.data(),
+        }
+    }
+
+    /// Creates an instruction to propose a new authority for the registry.
+    /// The default pubkey cancels a pending proposal.
+    pub fn propose_authority(&self, new_authority: Pubkey) -> Instruction {
+        let accounts = ix_accounts::ProposeAuthority {
+            authority: self.authority,
+            registry_account: self.registry_address(),
+        }
+        .to_account_metas(None);
+
+        Instruction {
+            program_id: LOOKUP_REGISTRY_ID,
+            accounts,
+            data: ix_data::ProposeAuthority { new_authority }.data(),
+        }
+    }
+
+    /// Creates an instruction for the builder's authority to accept a registry
+    /// that was proposed to it, along with all of the registry's pages.
+    pub fn accept_authority(&self, page_count: u8) -> Instruction {
+        let mut accounts = ix_accounts::AcceptAuthority {
+            new_authority: self.authority,
+            registry_account: self.registry_address(),
+        }
+        .to_account_metas(None);
+        accounts.extend(
+            (1..page_count).map(|page| AccountMeta::new(self.registry_page_address(page), false)),
+        );
+
+        Instruction {
+            program_id: LOOKUP_REGISTRY_ID,
+            accounts,
+            data: ix_data::AcceptAuthority.data(),
+        }
+    }
+
+    /// Creates an instruction to migrate the builder's page of the registry to
+    /// the latest layout version.
+    pub fn migrate_registry(&self) -> Instruction {
+        let accounts = ix_accounts::MigrateRegistry {
+            authority: self.authority,
+            payer: self.payer,
+            registry_account: self.page_address(),
+            system_program: SYSTEM_PROGAM_ID,
+        }
+        .to_account_metas(None);
+
+        Instruction {
+            program_id: LOOKUP_REGISTRY_ID,
+            accounts,
+            data: ix_data::MigrateRegistry.data(),
+        }
+    }
+
+    /// The address of the registry account, which is its first page.
     pub fn registry_address(&self) -> Pubkey {
-        Pubkey::find_program_address(&[self.authority.as_ref()], &LOOKUP_REGISTRY_ID).0
+        match &self.program {
+            Some(program) => derive_public_registry_address(program),
+            None => self.registry_page_address(0),
+        }
+    }
+
+    /// The address of the builder's page of the registry.
+    pub fn page_address(&self) -> Pubkey {
+        match &self.program {
+            // Public registries only have a single page
+            Some(program) => derive_public_registry_address(program),
+            None => self.registry_page_address(self.page),
+        }
+    }
+
+    /// The address of a page of the seed authority's registry.
+    fn registry_page_address(&self, page: u8) -> Pubkey {
+        derive_named_registry_page_address(
+            &self.seed_authority,
+            &self.name.unwrap_or_default(),
+            page,
+        )
     }
 }
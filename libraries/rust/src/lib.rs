@@ -1,6 +1,8 @@
 use anchor_lang::prelude::Pubkey;
+use lookup_table_registry::{registry_name_seed, REGISTRY_NAME_LEN};
 use solana_sdk::address_lookup_table_account::AddressLookupTableAccount;
 
+pub mod events;
 pub mod instructions;
 #[cfg(feature = "client")]
 pub mod reader;
@@ -17,6 +19,14 @@ pub use solana_address_lookup_table_program_gateway::ID as LOOKUP_TABLE_ID;
 pub struct Entry {
     pub discriminator: u64,
     pub lookup_address: Pubkey,
+    /// Whether the lookup table is frozen, and thus can never change
+    pub frozen: bool,
+    /// The human-readable label of the lookup table, empty if not set
+    pub label: String,
+    /// Metadata that the authority attached to the lookup table
+    pub metadata: [u8; lookup_table_registry::ENTRY_METADATA_LEN],
+    /// The slot when the lookup table was created, 0 if unknown
+    pub created_slot: u64,
     /// The list of addresses.
     ///
     /// It would be convenient to have this as a HashSet to remove duplicates,
@@ -36,6 +46,62 @@ impl From<Entry> for AddressLookupTableAccount {
     }
 }
 
+/// Derive the address of the registry account of an authority.
+pub fn derive_registry_address(authority: &Pubkey) -> Pubkey {
+    derive_named_registry_page_address(authority, &[0; REGISTRY_NAME_LEN], 0)
+}
+
+/// Derive the address of a page of the registry account of an authority.
+///
+/// The first page is the registry account itself.
+pub fn derive_registry_page_address(authority: &Pubkey, page: u8) -> Pubkey {
+    derive_named_registry_page_address(authority, &[0; REGISTRY_NAME_LEN], page)
+}
+
+/// Derive the address of a page of a named registry of an authority.
+///
+/// A name of all zeros is the registry derived from the authority alone,
+/// see [derive_registry_page_address].
+pub fn derive_named_registry_page_address(
+    authority: &Pubkey,
+    name: &[u8; REGISTRY_NAME_LEN],
+    page: u8,
+) -> Pubkey {
+    let page: &[u8] = if page == 0 { &[] } else { &[page] };
+    Pubkey::find_program_address(
+        &[authority.as_ref(), registry_name_seed(name), page],
+        &LOOKUP_TABLE_REGISTRY_ID,
+    )
+    .0
+}
+
+/// Convert the name of a registry to the fixed length bytes used in its seeds.
+///
+/// Returns `None` if the name is empty or longer than [REGISTRY_NAME_LEN] bytes.
+pub fn registry_name(name: &str) -> Option<[u8; REGISTRY_NAME_LEN]> {
+    if name.is_empty() || name.len() > REGISTRY_NAME_LEN {
+        return None;
+    }
+    let mut bytes = [0; REGISTRY_NAME_LEN];
+    bytes[..name.len()].copy_from_slice(name.as_bytes());
+    Some(bytes)
+}
+
+/// Derive the address of the public registry of a program.
+pub fn derive_public_registry_address(program: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            lookup_table_registry::PUBLIC_REGISTRY_SEED,
+            program.as_ref(),
+        ],
+        &LOOKUP_TABLE_REGISTRY_ID,
+    )
+    .0
+}
+
+/// Derive the address of a lookup table.
+///
+/// Lookup tables created by a registry use the registry account as their authority.
 pub fn derive_lookup_table_address(authority: &Pubkey, recent_block_slot: u64) -> Pubkey {
     solana_address_lookup_table_program_gateway::instruction::derive_lookup_table_address(
         authority,
@@ -43,3 +109,57 @@ pub fn derive_lookup_table_address(authority: &Pubkey, recent_block_slot: u64) -
     )
     .0
 }
+
+#[cfg(test)]
+mod tests {
+    use lookup_table_registry::RegistryAccount;
+
+    use super::*;
+
+    /// Find the bump of a registry page with the seeds that the program signs
+    /// with, and check that it is at the address derived by the client.
+    fn assert_signer_address(mut header: RegistryAccount, address: Pubkey) {
+        let signer_address = (0..=u8::MAX).rev().find_map(|bump| {
+            header.seed = [bump];
+            Pubkey::create_program_address(&header.signer_seeds(), &LOOKUP_TABLE_REGISTRY_ID).ok()
+        });
+        assert_eq!(signer_address, Some(address));
+    }
+
+    fn header(seed_authority: Pubkey, name: [u8; REGISTRY_NAME_LEN], page: u8) -> RegistryAccount {
+        let mut header: RegistryAccount = bytemuck::Zeroable::zeroed();
+        header.seed_authority = seed_authority;
+        header.name = name;
+        header.page = page;
+        header
+    }
+
+    #[test]
+    fn test_registry_signer_seeds() {
+        let authority = Pubkey::new_unique();
+        let unnamed = [0; REGISTRY_NAME_LEN];
+        let name = registry_name("devnet").unwrap();
+
+        assert_signer_address(
+            header(authority, unnamed, 0),
+            derive_registry_address(&authority),
+        );
+        assert_signer_address(
+            header(authority, unnamed, 3),
+            derive_registry_page_address(&authority, 3),
+        );
+        assert_signer_address(
+            header(authority, name, 0),
+            derive_named_registry_page_address(&authority, &name, 0),
+        );
+        assert_signer_address(
+            header(authority, name, 3),
+            derive_named_registry_page_address(&authority, &name, 3),
+        );
+
+        let program = Pubkey::new_unique();
+        let mut public = header(program, unnamed, 0);
+        public.public = 1;
+        assert_signer_address(public, derive_public_registry_address(&program));
+    }
+}
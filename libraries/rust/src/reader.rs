@@ -13,10 +13,16 @@ use solana_sdk::{
 
 use crate::common::{AccountReader, Registry};
 
+/// The key of a cached registry, which is its authority and optional name
+type RegistryKey = (Pubkey, Option<String>);
+
 /// A client suitable for querying instruction registries for authorities.
+///
+/// Methods take an optional registry name, which selects the named registry
+/// of each authority instead of the registry derived from the authority alone.
 pub struct LookupRegistryReader<A> {
     rpc: A,
-    cache: Arc<RwLock<endorphin::HashMap<Pubkey, Registry, TTLPolicy>>>,
+    cache: Arc<RwLock<endorphin::HashMap<RegistryKey, Registry, TTLPolicy>>>,
 }
 
 impl<A: Clone> Clone for LookupRegistryReader<A> {
@@ -41,25 +47,37 @@ impl<A: Deref<Target = X>, X: AccountReader> LookupRegistryReader<A> {
     /// Fetch the latest registry addresses for specific authorities.
     ///
     /// Returns the authorities that were not found or otherwise incurred some error
-    pub async fn update_registries(&self, authorities: &[Pubkey]) -> Vec<Pubkey> {
+    pub async fn update_registries(
+        &self,
+        authorities: &[Pubkey],
+        name: Option<&str>,
+    ) -> Vec<Pubkey> {
         let mut errors = Vec::with_capacity(authorities.len());
         for authority in authorities {
-            let Ok(registry) = Registry::fetch(&*self.rpc, authority).await else {
+            let Ok(registry) = Registry::fetch(&*self.rpc, authority, name).await else {
                 errors.push(*authority);
                 continue;
             };
             let mut writer = self.cache.write().unwrap();
-            writer.insert(*authority, registry, Duration::from_secs(3600));
+            writer.insert(
+                (*authority, name.map(str::to_string)),
+                registry,
+                Duration::from_secs(3600),
+            );
         }
         errors
     }
 
     /// Returns all the lookup tables that are in the registries owned by the
     /// provided authorities.
-    pub async fn get_tables(&self, authorities: &[Pubkey]) -> Vec<AddressLookupTableAccount> {
+    pub async fn get_tables(
+        &self,
+        authorities: &[Pubkey],
+        name: Option<&str>,
+    ) -> Vec<AddressLookupTableAccount> {
         let mut ret = vec![];
         for authority in authorities {
-            if let Some(r) = self.get_registry(authority).await {
+            if let Some(r) = self.get_registry(authority, name).await {
                 ret.extend(r.tables.into_iter().map(Into::into));
             }
         }
@@ -72,6 +90,7 @@ impl<A: Deref<Target = X>, X: AccountReader> LookupRegistryReader<A> {
         &self,
         instructions: &[Instruction],
         authorities: &[Pubkey],
+        name: Option<&str>,
     ) -> FindAddressesResult {
         let mut accounts = HashSet::with_capacity(256);
         for ix in instructions {
@@ -86,7 +105,7 @@ impl<A: Deref<Target = X>, X: AccountReader> LookupRegistryReader<A> {
         let mut matches = vec![];
         for authority in authorities {
             let reader = self.cache.read().unwrap();
-            let Some(registry) = reader.get(authority) else {
+            let Some(registry) = reader.get(&(*authority, name.map(str::to_string))) else {
                 continue;
             };
             // We have a registry, find matches.
@@ -133,20 +152,21 @@ impl<A: Deref<Target = X>, X: AccountReader> LookupRegistryReader<A> {
         }
     }
 
-    pub async fn get_registry(&self, authority: &Pubkey) -> Option<Registry> {
+    pub async fn get_registry(&self, authority: &Pubkey, name: Option<&str>) -> Option<Registry> {
+        let key = (*authority, name.map(str::to_string));
         let registry = {
             let reader = self.cache.read().unwrap();
-            reader.get(authority).cloned()
+            reader.get(&key).cloned()
         };
         match registry {
             Some(registry) => Some(registry),
             None => {
-                let Ok(registry) = Registry::fetch(&*self.rpc, authority).await else {
+                let Ok(registry) = Registry::fetch(&*self.rpc, authority, name).await else {
                     return None;
                 };
                 let mut writer = self.cache.write().unwrap();
-                writer.insert(*authority, registry, Duration::from_secs(3600));
-                writer.get(authority).cloned()
+                writer.insert(key.clone(), registry, Duration::from_secs(3600));
+                writer.get(&key).cloned()
             }
         }
     }
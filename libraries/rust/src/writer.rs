@@ -2,8 +2,11 @@
 
 use std::{collections::HashSet, sync::Arc};
 
-use anchor_lang::{prelude::Pubkey, AccountDeserialize};
-use lookup_table_registry::{RegistryAccount, RegistryEntry};
+use anchor_lang::prelude::Pubkey;
+use lookup_table_registry::{
+    discriminator, version, RegistryEntry, RegistryPage, DEACTIVATION_COOLDOWN, ENTRY_LABEL_LEN,
+    ENTRY_METADATA_LEN, MAX_CREATE_LOOKUP_TABLES,
+};
 use solana_address_lookup_table_program_gateway::state::AddressLookupTable;
 use solana_client::{nonblocking::rpc_client::RpcClient, rpc_config::RpcSendTransactionConfig};
 use solana_sdk::{
@@ -12,9 +15,13 @@ use solana_sdk::{
     signer::Signer, transaction::Transaction,
 };
 
-use crate::common::{LookupRegistryError, LookupRegistryResult};
+use crate::common::{LookupRegistryError, LookupRegistryResult, Registry};
 use crate::instructions::InstructionBuilder;
 
+/// The number of instructions sent in a transaction when a writer sends
+/// instructions for many lookup tables or pages.
+const INSTRUCTIONS_PER_TRANSACTION: usize = 8;
+
 /// A writer client that creates and updates a registry
 pub struct LookupRegistryWriter {
     rpc: Arc<RpcClient>,
@@ -34,6 +41,30 @@ impl LookupRegistryWriter {
         }
     }
 
+    /// Use a registry that was transferred to the authority.
+    ///
+    /// A registry keeps the address derived from its original authority.
+    pub fn with_seed_authority(mut self, seed_authority: Pubkey) -> Self {
+        self.builder = self.builder.with_seed_authority(seed_authority);
+        self.registry_address = self.builder.registry_address();
+        self
+    }
+
+    /// Use a named registry of the authority, such as one per environment,
+    /// which is separate from the registry derived from the authority alone.
+    /// The named registry is created with [Self::init_registry].
+    ///
+    /// Errors if the name is empty or longer than [lookup_table_registry::REGISTRY_NAME_LEN] bytes.
+    #[allow(clippy::result_large_err)]
+    pub fn with_name(mut self, name: &str) -> LookupRegistryResult<Self> {
+        let name = crate::registry_name(name).ok_or_else(|| {
+            LookupRegistryError::InvalidArgument(format!("Invalid registry name {name}"))
+        })?;
+        self.builder = self.builder.with_name(name);
+        self.registry_address = self.builder.registry_address();
+        Ok(self)
+    }
+
     /// Create a new empty lookup registry
     pub async fn new_or_create(
         rpc: &Arc<RpcClient>,
@@ -76,25 +107,52 @@ impl LookupRegistryWriter {
         })
     }
 
-    /// Get the registry account's state.
+    /// Create the registry of the writer if it doesn't exist yet, which is the
+    /// named registry if the writer has a name, see [Self::with_name].
+    pub async fn init_registry(
+        &self,
+        payer: Option<&Pubkey>,
+        signer: &dyn Signer,
+    ) -> LookupRegistryResult<()> {
+        if self.rpc.get_account(&self.registry_address).await.is_ok() {
+            return Ok(());
+        }
+        let ix = self.builder.init_registry();
+        self.send_transaction(&[ix], payer, signer).await?;
+
+        Ok(())
+    }
+
+    /// Get the registry account's state, which is the state of its first page.
+    ///
+    /// Registries on an older version are returned in the latest layout, with
+    /// their stored version.
     ///
     /// Errors:
     /// - Registry has not been created
-    pub async fn get_registry(&self) -> LookupRegistryResult<RegistryAccount> {
+    pub async fn get_registry(&self) -> LookupRegistryResult<RegistryPage> {
         let account = self.rpc.get_account(&self.registry_address).await?;
-        let registry_account = RegistryAccount::try_deserialize(&mut account.data())?;
+        let registry_account = RegistryPage::try_deserialize_versioned(&mut account.data())?;
         Ok(registry_account)
     }
 
+    /// Get the state of all the pages of the registry.
+    ///
+    /// Errors:
+    /// - Registry has not been created
+    pub async fn get_registry_pages(&self) -> LookupRegistryResult<Vec<RegistryPage>> {
+        Registry::fetch_pages(&*self.rpc, &self.registry_address).await
+    }
+
     /// Find lookup table addresses in the registry by a discriminator
     pub async fn find_lookup_table_addresses(
         &self,
         discriminator: u64,
     ) -> LookupRegistryResult<Vec<Pubkey>> {
-        let registry = self.get_registry().await?;
-        let addresses = registry
-            .tables
+        let pages = self.get_registry_pages().await?;
+        let addresses = pages
             .iter()
+            .flat_map(|page| page.tables.iter())
             .filter_map(|table| {
                 if table.discriminator == discriminator {
                     Some(table.table)
@@ -106,29 +164,67 @@ impl LookupRegistryWriter {
         Ok(addresses)
     }
 
+    /// Get the deactivated lookup tables in the registry, with the slot after
+    /// which each of them can be closed at the earliest.
+    ///
+    /// Tables that were deactivated before the registry recorded deactivation
+    /// slots are looked up on chain, and skipped if they can't be found.
+    pub async fn get_closeable_lookup_tables(&self) -> LookupRegistryResult<Vec<(Pubkey, u64)>> {
+        let pages = self.get_registry_pages().await?;
+        let deactivated = pages
+            .iter()
+            .flat_map(|page| page.tables.iter())
+            .filter(|entry| entry.discriminator == discriminator::DEACTIVATED)
+            .collect::<Vec<_>>();
+        let unknown = deactivated
+            .iter()
+            .filter(|entry| entry.closeable_after_slot().is_none())
+            .map(|entry| entry.table)
+            .collect::<Vec<_>>();
+        let mut unknown_slots = Vec::with_capacity(unknown.len());
+        for chunk in unknown.chunks(100) {
+            for account in self.rpc.get_multiple_accounts(chunk).await? {
+                let slot = account
+                    .and_then(|account| {
+                        AddressLookupTable::deserialize(account.data())
+                            .ok()
+                            .map(|table| table.meta.deactivation_slot)
+                    })
+                    .map(|slot| slot.saturating_add(DEACTIVATION_COOLDOWN));
+                unknown_slots.push(slot);
+            }
+        }
+        let mut unknown_slots = unknown_slots.into_iter();
+        let tables = deactivated
+            .into_iter()
+            .filter_map(|entry| {
+                let slot = entry
+                    .closeable_after_slot()
+                    .or_else(|| unknown_slots.next().flatten())?;
+                Some((entry.table, slot))
+            })
+            .collect();
+
+        Ok(tables)
+    }
+
     /// Get a single lookup table in the registry
     pub async fn get_lookup_table(
         &self,
         lookup_table: Pubkey,
     ) -> LookupRegistryResult<(RegistryEntry, AddressLookupTableAccount)> {
-        // Get the reigstry and lookup table
-        let accounts = self
-            .rpc
-            .get_multiple_accounts(&[self.registry_address, lookup_table])
-            .await?;
-        // Elide bound checks
-        assert_eq!(accounts.len(), 2);
-        let (Some(registry_account), Some(lookup_table_account)) = (&accounts[0], &accounts[1]) else {
-            return Err(LookupRegistryError::InvalidArgument("Registry account or lookup table not found".to_string()));
-        };
-        let registry_account = RegistryAccount::try_deserialize(&mut registry_account.data())?;
         // Check if the registry has the lookup table, otherwise it doesn't own it
-        let Some(registry_entry) = registry_account
-            .tables
-            .iter()
-            .find(|table| table.table == lookup_table)
-         else {
-            return Err(LookupRegistryError::InvalidArgument("Registry account does not own the lookup account".to_string()));
+        let (_, registry_entry) = self.find_entry(lookup_table).await?;
+        let Some(lookup_table_account) = self
+            .rpc
+            .get_multiple_accounts(&[lookup_table])
+            .await?
+            .pop()
+            .flatten()
+        else {
+            return Err(LookupRegistryError::InvalidArgument(
+                "Lookup table not found".to_string(),
+            ));
         };
         // Now deserialize the lookup table
         let table = {
@@ -139,49 +235,572 @@ impl LookupRegistryWriter {
                 addresses: table.addresses.to_vec(),
             }
         };
-        Ok((registry_entry.clone(), table))
+        Ok((registry_entry, table))
+    }
+
+    /// Find the page of the registry and the entry of a lookup table
+    async fn find_entry(&self, lookup_table: Pubkey) -> LookupRegistryResult<(u8, RegistryEntry)> {
+        let pages = self.get_registry_pages().await?;
+        pages
+            .into_iter()
+            .find_map(|page| {
+                let index = page.page;
+                page.tables
+                    .into_iter()
+                    .find(|entry| entry.table == lookup_table)
+                    .map(|entry| (index, entry))
+            })
+            .ok_or_else(|| {
+                LookupRegistryError::InvalidArgument(
+                    "Registry account does not own the lookup account".to_string(),
+                )
+            })
+    }
+
+    /// The recipient of lamports from a page, which is the recipient configured
+    /// on the page or the payer of the writer.
+    fn recipient(&self, page: &RegistryPage) -> Pubkey {
+        if page.recipient == Pubkey::default() {
+            self.builder.payer
+        } else {
+            page.recipient
+        }
+    }
+
+    /// Get an instruction builder for the page that a lookup table is in
+    async fn page_builder(&self, lookup_table: Pubkey) -> LookupRegistryResult<InstructionBuilder> {
+        let (page, _) = self.find_entry(lookup_table).await?;
+        Ok(self.builder.clone().with_page(page))
     }
 
-    /// Create a new lookup table in the registry
+    /// Create a new lookup table in the registry.
+    ///
+    /// The table is created in the first page that has space, and a new page
+    /// is added to the registry if all pages are full.
     pub async fn create_lookup_table(
         &self,
         payer: Option<&Pubkey>,
         signer: &dyn Signer,
         discriminator: u64,
     ) -> LookupRegistryResult<(Pubkey, u64)> {
-        // Introduce a small delay to prevent slot conflicts
-        tokio::time::sleep(std::time::Duration::from_secs(3)).await;
-        let recent_slot = self.rpc.get_slot().await?;
-        let (ix, table) = self.builder.create_lookup_table(recent_slot, discriminator);
+        self.create_and_extend_lookup_table(payer, signer, discriminator, &[])
+            .await
+    }
 
-        self.send_transaction(&[ix], payer, signer).await?;
+    /// Create a new lookup table in the registry with an initial list of
+    /// addresses, in a single transaction.
+    ///
+    /// See [LookupRegistryWriter::create_lookup_table] for how the page is selected.
+    pub async fn create_and_extend_lookup_table(
+        &self,
+        payer: Option<&Pubkey>,
+        signer: &dyn Signer,
+        discriminator: u64,
+        addresses: &[Pubkey],
+    ) -> LookupRegistryResult<(Pubkey, u64)> {
+        let pages = self.get_registry_pages().await?;
+        let (builder, init_page, last_created_slot) = self.page_with_space(&pages, 1)?;
+        let mut instructions = Vec::from_iter(init_page);
+        let recent_slot = self.next_recent_slot(last_created_slot).await?;
+        let (ix, table) = if addresses.is_empty() {
+            builder.create_lookup_table(recent_slot, discriminator)
+        } else {
+            builder.create_and_extend_lookup_table(recent_slot, discriminator, addresses)
+        };
+        instructions.push(ix);
+
+        self.send_transaction(&instructions, payer, signer).await?;
 
         Ok((table, recent_slot))
     }
 
+    /// Create multiple lookup tables in the registry in a single transaction.
+    ///
+    /// The tables are created in the first page that has space for all of them,
+    /// and a new page is added to the registry if no page has enough space.
+    /// Up to [MAX_CREATE_LOOKUP_TABLES] tables can be created in a transaction.
+    ///
+    /// Returns the addresses of the tables with the slots they were derived from.
+    pub async fn create_lookup_tables(
+        &self,
+        payer: Option<&Pubkey>,
+        signer: &dyn Signer,
+        discriminator: u64,
+        count: usize,
+    ) -> LookupRegistryResult<Vec<(Pubkey, u64)>> {
+        if count == 0 || count > MAX_CREATE_LOOKUP_TABLES {
+            return Err(LookupRegistryError::InvalidArgument(format!(
+                "Can create between 1 and {MAX_CREATE_LOOKUP_TABLES} lookup tables"
+            )));
+        }
+        let pages = self.get_registry_pages().await?;
+        let (builder, init_page, last_created_slot) = self.page_with_space(&pages, count)?;
+        let mut instructions = Vec::from_iter(init_page);
+        let recent_slots = self.next_recent_slots(last_created_slot, count).await?;
+        let (ix, tables) = builder.create_lookup_tables(&recent_slots, discriminator);
+        instructions.push(ix);
+
+        self.send_transaction(&instructions, payer, signer).await?;
+
+        Ok(tables.into_iter().zip(recent_slots).collect())
+    }
+
+    /// Import a lookup table that was created outside of the registry, and
+    /// whose authority is the registry's authority.
+    ///
+    /// The table is added to the first page that has space, and a new page is
+    /// added to the registry if all pages are full.
+    pub async fn import(
+        &self,
+        lookup_table: Pubkey,
+        discriminator: u64,
+        payer: Option<&Pubkey>,
+        signer: &dyn Signer,
+    ) -> LookupRegistryResult<()> {
+        let pages = self.get_registry_pages().await?;
+        if pages
+            .iter()
+            .flat_map(|page| page.tables.iter())
+            .any(|entry| entry.table == lookup_table)
+        {
+            return Err(LookupRegistryError::InvalidArgument(
+                "The lookup table is already in the registry".to_string(),
+            ));
+        }
+        let (builder, init_page, _) = self.page_with_space(&pages, 1)?;
+        let mut instructions = Vec::from_iter(init_page);
+        instructions.push(builder.import_lookup_table(lookup_table, discriminator));
+
+        self.send_transaction(&instructions, payer, signer).await?;
+
+        Ok(())
+    }
+
+    /// Get the builder for the first page with space for a number of lookup
+    /// tables, with the last slot used to create a table in the page.
+    ///
+    /// If no page has enough space, a new page is used, along with the
+    /// instruction to initialize it.
+    #[allow(clippy::result_large_err)]
+    fn page_with_space(
+        &self,
+        pages: &[RegistryPage],
+        count: usize,
+    ) -> LookupRegistryResult<(InstructionBuilder, Option<Instruction>, u64)> {
+        if let Some(page) = pages.iter().find(|page| page.available_entries() >= count) {
+            return Ok((
+                self.builder.clone().with_page(page.page),
+                None,
+                page.last_created_slot,
+            ));
+        }
+        let page = u8::try_from(pages.len()).map_err(|_| {
+            LookupRegistryError::GeneralError("The registry has too many pages".to_string())
+        })?;
+        Ok((
+            self.builder.clone().with_page(page),
+            Some(self.builder.init_registry_page(page)),
+            0,
+        ))
+    }
+
+    /// Get a number of recent slots that are after the last slot used to create
+    /// a lookup table in a registry page, waiting for them if necessary.
+    ///
+    /// Only slots with a block can be used to derive lookup tables.
+    async fn next_recent_slots(
+        &self,
+        last_created_slot: u64,
+        count: usize,
+    ) -> LookupRegistryResult<Vec<u64>> {
+        loop {
+            let slot = self.rpc.get_slot().await?;
+            // Lookup tables can only be created from slots that are still in
+            // the slot hashes, which has the last 512 slots.
+            let start_slot = (last_created_slot + 1).max(slot.saturating_sub(150));
+            if slot >= start_slot {
+                let blocks = self.rpc.get_blocks(start_slot, Some(slot)).await?;
+                if blocks.len() >= count {
+                    return Ok(blocks[blocks.len() - count..].to_vec());
+                }
+            }
+            // Wait for about a slot
+            tokio::time::sleep(std::time::Duration::from_millis(400)).await;
+        }
+    }
+
+    /// Get a recent slot that is after the last slot used to create a lookup
+    /// table in a registry page, waiting for one if necessary.
+    async fn next_recent_slot(&self, last_created_slot: u64) -> LookupRegistryResult<u64> {
+        loop {
+            let slot = self.rpc.get_slot().await?;
+            if slot > last_created_slot {
+                return Ok(slot);
+            }
+            // Wait for about a slot
+            tokio::time::sleep(std::time::Duration::from_millis(400)).await;
+        }
+    }
+
     /// Removes a lookup table by either deactivating or closing it.
     /// Lookup tables cannot be closed while active, and require deactivating for
     /// a number of slots before being closed.
     ///
     /// Callers can invoke this function twice to close a lookup table.
+    ///
+    /// The lamports of a closed table go to the recipient of its registry
+    /// page, or to the payer of the writer if the page has no recipient.
     pub async fn remove_lookup_table(
         &self,
         lookup_table: Pubkey,
         payer: Option<&Pubkey>,
         signer: &dyn Signer,
     ) -> LookupRegistryResult<()> {
-        let ix = self.builder.remove_lookup_table(lookup_table);
+        let pages = self.get_registry_pages().await?;
+        let page = pages
+            .iter()
+            .find(|page| page.find_entry(&lookup_table).is_ok())
+            .ok_or_else(|| {
+                LookupRegistryError::InvalidArgument(
+                    "Registry account does not own the lookup account".to_string(),
+                )
+            })?;
+        let ix = self
+            .builder
+            .clone()
+            .with_page(page.page)
+            .remove_lookup_table(lookup_table, self.recipient(page));
 
         self.send_transaction(&[ix], payer, signer).await?;
 
         Ok(())
     }
 
+    /// Closes the deactivated lookup tables of the registry whose cooldown has
+    /// elapsed, returning their lamports to the recipient of their page.
+    ///
+    /// Anyone can close these tables, so the signer doesn't have to be the
+    /// authority. Pages without a recipient, and tables owned by the authority,
+    /// are skipped. Returns the number of lookup tables that were closed.
+    pub async fn close_lookup_tables(
+        &self,
+        payer: Option<&Pubkey>,
+        signer: &dyn Signer,
+    ) -> LookupRegistryResult<usize> {
+        let closeable = self.get_closeable_lookup_tables().await?;
+        let slot = self.rpc.get_slot().await?;
+        let pages = self.get_registry_pages().await?;
+        let instructions = pages
+            .iter()
+            .filter(|page| page.recipient != Pubkey::default())
+            .flat_map(|page| {
+                let builder = self.builder.clone().with_page(page.page);
+                page.tables
+                    .iter()
+                    .filter(|entry| !entry.is_legacy_authority())
+                    .filter(|entry| {
+                        closeable
+                            .iter()
+                            .any(|(table, after_slot)| *table == entry.table && slot > *after_slot)
+                    })
+                    .map(move |entry| builder.close_lookup_table(entry.table, page.recipient))
+            })
+            .collect::<Vec<_>>();
+        self.send_instructions(&instructions, payer, signer).await?;
+
+        Ok(instructions.len())
+    }
+
+    /// Sets the recipient of the lookup tables that anyone can close on all
+    /// pages of the registry, see [LookupRegistryWriter::close_lookup_tables].
+    ///
+    /// Setting the default pubkey disables closing tables without the authority.
+    pub async fn set_recipient(
+        &self,
+        recipient: Pubkey,
+        payer: Option<&Pubkey>,
+        signer: &dyn Signer,
+    ) -> LookupRegistryResult<()> {
+        let registry = self.get_registry().await?;
+        let instructions = (0..registry.page_count.max(1))
+            .map(|page| {
+                self.builder
+                    .clone()
+                    .with_page(page)
+                    .set_recipient(recipient)
+            })
+            .collect::<Vec<_>>();
+        self.send_instructions(&instructions, payer, signer).await?;
+
+        Ok(())
+    }
+
+    /// Allows a delegate to extend the lookup tables with a discriminator on
+    /// all pages of the registry.
+    ///
+    /// The delegate can then append to lookup tables with a writer whose
+    /// authority is the delegate, see [LookupRegistryWriter::with_seed_authority].
+    pub async fn add_delegate(
+        &self,
+        delegate: Pubkey,
+        discriminator: u64,
+        payer: Option<&Pubkey>,
+        signer: &dyn Signer,
+    ) -> LookupRegistryResult<()> {
+        let registry = self.get_registry().await?;
+        let instructions = (0..registry.page_count.max(1))
+            .map(|page| {
+                self.builder
+                    .clone()
+                    .with_page(page)
+                    .add_delegate(delegate, discriminator)
+            })
+            .collect::<Vec<_>>();
+        self.send_instructions(&instructions, payer, signer).await?;
+
+        Ok(())
+    }
+
+    /// Revokes a delegate's access to the lookup tables with a discriminator
+    /// on all pages of the registry that have the delegate.
+    pub async fn remove_delegate(
+        &self,
+        delegate: Pubkey,
+        discriminator: u64,
+        payer: Option<&Pubkey>,
+        signer: &dyn Signer,
+    ) -> LookupRegistryResult<()> {
+        let pages = self.get_registry_pages().await?;
+        let instructions = pages
+            .iter()
+            .filter(|page| page.is_delegate(&delegate, discriminator))
+            .map(|page| {
+                self.builder
+                    .clone()
+                    .with_page(page.page)
+                    .remove_delegate(delegate, discriminator)
+            })
+            .collect::<Vec<_>>();
+        self.send_instructions(&instructions, payer, signer).await?;
+
+        Ok(())
+    }
+
+    /// Freezes a lookup table so that it can never change.
+    ///
+    /// Frozen lookup tables can't be extended or removed, and empty tables
+    /// can't be frozen.
+    pub async fn freeze_lookup_table(
+        &self,
+        lookup_table: Pubkey,
+        payer: Option<&Pubkey>,
+        signer: &dyn Signer,
+    ) -> LookupRegistryResult<()> {
+        let ix = self
+            .page_builder(lookup_table)
+            .await?
+            .freeze_lookup_table(lookup_table);
+
+        self.send_transaction(&[ix], payer, signer).await?;
+
+        Ok(())
+    }
+
+    /// Removes a lookup table owned by the authority from the registry without
+    /// closing it. Lookup tables owned by the registry account can't be detached.
+    pub async fn detach_lookup_table(
+        &self,
+        lookup_table: Pubkey,
+        payer: Option<&Pubkey>,
+        signer: &dyn Signer,
+    ) -> LookupRegistryResult<()> {
+        let ix = self
+            .page_builder(lookup_table)
+            .await?
+            .detach_lookup_table(lookup_table);
+
+        self.send_transaction(&[ix], payer, signer).await?;
+
+        Ok(())
+    }
+
+    /// Sets the label and metadata of a lookup table.
+    ///
+    /// The label can be up to [ENTRY_LABEL_LEN] bytes long.
+    pub async fn set_entry_label(
+        &self,
+        lookup_table: Pubkey,
+        label: &str,
+        metadata: [u8; ENTRY_METADATA_LEN],
+        payer: Option<&Pubkey>,
+        signer: &dyn Signer,
+    ) -> LookupRegistryResult<()> {
+        if label.len() > ENTRY_LABEL_LEN {
+            return Err(LookupRegistryError::InvalidArgument(format!(
+                "The label can be up to {ENTRY_LABEL_LEN} bytes long"
+            )));
+        }
+        let mut label_bytes = [0; ENTRY_LABEL_LEN];
+        label_bytes[..label.len()].copy_from_slice(label.as_bytes());
+        let ix = self.page_builder(lookup_table).await?.set_entry_label(
+            lookup_table,
+            label_bytes,
+            metadata,
+        );
+
+        self.send_transaction(&[ix], payer, signer).await?;
+
+        Ok(())
+    }
+
+    /// Closes the registry account, its pages, and any of its deactivated
+    /// lookup tables, returning their lamports to the recipient of each
+    /// page or to the payer of the writer.
+    ///
+    /// Errors if the registry still has active lookup tables, or if a deactivated
+    /// table can't be closed yet.
+    pub async fn close_registry_account(
+        &self,
+        payer: Option<&Pubkey>,
+        signer: &dyn Signer,
+    ) -> LookupRegistryResult<()> {
+        let pages = self.get_registry_pages().await?;
+        // Pages can only be closed from the last one
+        for page in pages.iter().rev() {
+            let deactivated_tables = page
+                .tables
+                .iter()
+                .filter(|entry| entry.discriminator == discriminator::DEACTIVATED)
+                .map(|entry| entry.table)
+                .collect::<Vec<_>>();
+            let ix = if page.page == 0 {
+                self.builder
+                    .close_registry_account(&deactivated_tables, self.recipient(page))
+            } else {
+                self.builder
+                    .clone()
+                    .with_page(page.page)
+                    .close_registry_page(&deactivated_tables, self.recipient(page))
+            };
+
+            self.send_transaction(&[ix], payer, signer).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Compacts the pages of the registry that have empty entries, refunding
+    /// rent to the recipient of each page or to the payer of the writer.
+    ///
+    /// Live entries are moved to the front of their page if `reorder` is set,
+    /// otherwise only trailing empty entries are removed.
+    pub async fn compact_registry(
+        &self,
+        reorder: bool,
+        payer: Option<&Pubkey>,
+        signer: &dyn Signer,
+    ) -> LookupRegistryResult<()> {
+        let pages = self.get_registry_pages().await?;
+        let instructions = pages
+            .iter()
+            .filter(|page| page.empty_entries() > 0)
+            .map(|page| {
+                self.builder
+                    .clone()
+                    .with_page(page.page)
+                    .compact_registry(reorder, self.recipient(page))
+            })
+            .collect::<Vec<_>>();
+        self.send_instructions(&instructions, payer, signer).await?;
+
+        Ok(())
+    }
+
+    /// Repairs the pages of the registry whose counters don't match their
+    /// entries.
+    pub async fn repair_registry(
+        &self,
+        payer: Option<&Pubkey>,
+        signer: &dyn Signer,
+    ) -> LookupRegistryResult<()> {
+        let pages = self.get_registry_pages().await?;
+        let instructions = pages
+            .iter()
+            .filter(|page| !page.has_valid_counters(&page.tables))
+            .map(|page| self.builder.clone().with_page(page.page).repair_registry())
+            .collect::<Vec<_>>();
+        self.send_instructions(&instructions, payer, signer).await?;
+
+        Ok(())
+    }
+
+    /// Proposes a new authority for the registry, which the new authority has
+    /// to accept with [LookupRegistryWriter::accept_authority].
+    pub async fn propose_authority(
+        &self,
+        new_authority: Pubkey,
+        payer: Option<&Pubkey>,
+        signer: &dyn Signer,
+    ) -> LookupRegistryResult<()> {
+        let ix = self.builder.propose_authority(new_authority);
+
+        self.send_transaction(&[ix], payer, signer).await?;
+
+        Ok(())
+    }
+
+    /// Accepts a registry that was proposed to the writer's authority.
+    ///
+    /// The writer should be created with [LookupRegistryWriter::with_seed_authority]
+    /// as the registry's address is derived from its original authority.
+    pub async fn accept_authority(
+        &self,
+        payer: Option<&Pubkey>,
+        signer: &dyn Signer,
+    ) -> LookupRegistryResult<()> {
+        let registry = self.get_registry().await?;
+        let ix = self.builder.accept_authority(registry.page_count);
+
+        self.send_transaction(&[ix], payer, signer).await?;
+
+        Ok(())
+    }
+
+    /// Migrates the pages of the registry that are on an older layout version
+    /// to the latest one.
+    pub async fn migrate_registry(
+        &self,
+        payer: Option<&Pubkey>,
+        signer: &dyn Signer,
+    ) -> LookupRegistryResult<()> {
+        // Large registries are grown over multiple instructions before they
+        // are migrated, so migrate until all pages are on the latest version.
+        loop {
+            let pages = self.get_registry_pages().await?;
+            let instructions = pages
+                .iter()
+                .filter(|page| page.version != version::LATEST)
+                .map(|page| self.builder.clone().with_page(page.page).migrate_registry())
+                .collect::<Vec<_>>();
+            if instructions.is_empty() {
+                return Ok(());
+            }
+            self.send_instructions(&instructions, payer, signer).await?;
+        }
+    }
+
+    /// Appends the addresses that aren't in a lookup table yet.
+    ///
+    /// If `dedup_tables` is set, the program also drops addresses that were
+    /// appended by another writer since the lookup table was fetched, or that
+    /// are in the other active tables of the registry page, so that racing
+    /// writers don't fail. See [InstructionBuilder::append_to_lookup_table].
     // TODO: can return the remaining space, or all the accounts that exist
     pub async fn append_to_lookup_table(
         &self,
         lookup_table: Pubkey,
         addresses: &[Pubkey],
+        dedup_tables: Option<&[Pubkey]>,
         payer: Option<&Pubkey>,
         signer: &dyn Signer,
     ) -> LookupRegistryResult<()> {
@@ -193,17 +812,35 @@ impl LookupRegistryWriter {
             .collect::<HashSet<_>>()
             .into_iter()
             .collect::<Vec<_>>();
-        let ix = self.builder.append_to_lookup_table(
-            lookup_table,
-            &distinct_addresses[..],
-            entry.discriminator,
-        );
+        let ix = self
+            .page_builder(lookup_table)
+            .await?
+            .append_to_lookup_table(
+                lookup_table,
+                &distinct_addresses[..],
+                entry.discriminator,
+                dedup_tables,
+            );
 
         self.send_transaction(&[ix], payer, signer).await?;
 
         Ok(())
     }
 
+    /// Send instructions in transactions of [INSTRUCTIONS_PER_TRANSACTION]
+    async fn send_instructions(
+        &self,
+        instructions: &[Instruction],
+        payer: Option<&Pubkey>,
+        signer: &dyn Signer,
+    ) -> LookupRegistryResult<()> {
+        for chunk in instructions.chunks(INSTRUCTIONS_PER_TRANSACTION) {
+            self.send_transaction(chunk, payer, signer).await?;
+        }
+
+        Ok(())
+    }
+
     async fn send_transaction(
         &self,
         instructions: &[Instruction],
@@ -265,7 +902,7 @@ mod tests {
             addresses.push(Keypair::new().pubkey());
         });
         registry
-            .append_to_lookup_table(lookup_table, &addresses, None, &authority_keypair)
+            .append_to_lookup_table(lookup_table, &addresses, None, None, &authority_keypair)
             .await?;
 
         // Get the lookup table, it should have 12 entries
@@ -108,17 +108,25 @@ pub fn extend_lookup_table(
     )
 }
 
+/// Constructs an instruction that freezes an address lookup
+/// table so that it can never be closed or extended again. Empty
+/// lookup tables cannot be frozen.
+pub fn freeze_lookup_table(lookup_table_address: Pubkey, authority_address: Pubkey) -> Instruction {
+    Instruction::new_with_bincode(
+        id(),
+        &ProgramInstruction::FreezeLookupTable,
+        vec![
+            AccountMeta::new(lookup_table_address, false),
+            AccountMeta::new_readonly(authority_address, true),
+        ],
+    )
+}
+
 #[derive(Serialize)]
 enum ProgramInstruction {
-    CreateLookupTable {
-        recent_slot: Slot,
-        bump_seed: u8,
-    },
-    #[allow(dead_code)]
+    CreateLookupTable { recent_slot: Slot, bump_seed: u8 },
     FreezeLookupTable,
-    ExtendLookupTable {
-        new_addresses: Vec<Pubkey>,
-    },
+    ExtendLookupTable { new_addresses: Vec<Pubkey> },
 }
 
 #[cfg(test)]
@@ -160,6 +168,18 @@ mod test {
         }
     }
 
+    #[test]
+    fn freeze_lookup_table() {
+        let addr = addresses();
+        for i in 0..6 {
+            let n = i * 2;
+            assert_eq!(
+                real::freeze_lookup_table(addr[n], addr[n + 1]),
+                super::freeze_lookup_table(addr[n], addr[n + 1]),
+            );
+        }
+    }
+
     #[test]
     fn create_lookup_table() {
         let addr = addresses();
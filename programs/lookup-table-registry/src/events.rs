@@ -0,0 +1,177 @@
+use anchor_lang::prelude::*;
+
+/// A registry account was initialized
+#[event]
+#[derive(Debug, Clone)]
+pub struct RegistryInitialized {
+    pub registry: Pubkey,
+    pub authority: Pubkey,
+}
+
+/// The public registry of a program was initialized
+#[event]
+#[derive(Debug, Clone)]
+pub struct PublicRegistryInitialized {
+    pub registry: Pubkey,
+    pub program: Pubkey,
+    pub curator: Pubkey,
+}
+
+/// A page was added to a registry
+#[event]
+#[derive(Debug, Clone)]
+pub struct RegistryPageInitialized {
+    pub registry: Pubkey,
+    pub registry_page: Pubkey,
+    pub page: u8,
+}
+
+/// A registry account or one of its pages was closed
+#[event]
+#[derive(Debug, Clone)]
+pub struct RegistryClosed {
+    pub registry: Pubkey,
+    pub page: u8,
+    pub recipient: Pubkey,
+}
+
+/// A registry account was migrated to a new layout version
+#[event]
+#[derive(Debug, Clone)]
+pub struct RegistryMigrated {
+    pub registry: Pubkey,
+    pub version: u8,
+}
+
+/// Empty entries were removed from a registry page, refunding rent
+#[event]
+#[derive(Debug, Clone)]
+pub struct RegistryCompacted {
+    pub registry: Pubkey,
+    pub entries: u8,
+    pub refund: u64,
+}
+
+/// The counters of a registry page were recomputed from its entries
+#[event]
+#[derive(Debug, Clone)]
+pub struct RegistryRepaired {
+    pub registry: Pubkey,
+    pub len: u8,
+    pub capacity: u8,
+}
+
+/// A lookup table was created in a registry
+#[event]
+#[derive(Debug, Clone)]
+pub struct LookupTableCreated {
+    pub registry: Pubkey,
+    pub table: Pubkey,
+    pub discriminator: u64,
+    pub recent_slot: u64,
+}
+
+/// A lookup table created outside of the registry was added to it
+#[event]
+#[derive(Debug, Clone)]
+pub struct LookupTableImported {
+    pub registry: Pubkey,
+    pub table: Pubkey,
+    pub discriminator: u64,
+}
+
+/// Addresses were added to a lookup table
+#[event]
+#[derive(Debug, Clone)]
+pub struct LookupTableExtended {
+    pub registry: Pubkey,
+    pub table: Pubkey,
+    pub count: u32,
+}
+
+/// A lookup table was deactivated
+#[event]
+#[derive(Debug, Clone)]
+pub struct LookupTableDeactivated {
+    pub registry: Pubkey,
+    pub table: Pubkey,
+    pub slot: u64,
+}
+
+/// A lookup table was closed, and removed from its registry
+#[event]
+#[derive(Debug, Clone)]
+pub struct LookupTableClosed {
+    pub registry: Pubkey,
+    pub table: Pubkey,
+    pub recipient: Pubkey,
+}
+
+/// The recipient of the rent of lookup tables closed by anyone was set
+#[event]
+#[derive(Debug, Clone)]
+pub struct RecipientSet {
+    pub registry: Pubkey,
+    pub recipient: Pubkey,
+}
+
+/// A delegate was allowed to extend the lookup tables with a discriminator
+#[event]
+#[derive(Debug, Clone)]
+pub struct DelegateAdded {
+    pub registry: Pubkey,
+    pub delegate: Pubkey,
+    pub discriminator: u64,
+}
+
+/// A delegate's access to the lookup tables with a discriminator was revoked
+#[event]
+#[derive(Debug, Clone)]
+pub struct DelegateRemoved {
+    pub registry: Pubkey,
+    pub delegate: Pubkey,
+    pub discriminator: u64,
+}
+
+/// A lookup table was removed from its registry without being closed
+#[event]
+#[derive(Debug, Clone)]
+pub struct LookupTableDetached {
+    pub registry: Pubkey,
+    pub table: Pubkey,
+}
+
+/// A lookup table was frozen
+#[event]
+#[derive(Debug, Clone)]
+pub struct LookupTableFrozen {
+    pub registry: Pubkey,
+    pub table: Pubkey,
+}
+
+/// The label or metadata of a lookup table was set
+#[event]
+#[derive(Debug, Clone)]
+pub struct EntryLabelSet {
+    pub registry: Pubkey,
+    pub table: Pubkey,
+}
+
+/// A new authority was proposed for a registry, or a proposal was cancelled
+/// if the pending authority is the default pubkey.
+#[event]
+#[derive(Debug, Clone)]
+pub struct AuthorityProposed {
+    pub registry: Pubkey,
+    pub authority: Pubkey,
+    pub pending_authority: Pubkey,
+}
+
+/// The authority of a registry changed
+#[event]
+#[derive(Debug, Clone)]
+pub struct AuthorityChanged {
+    pub registry: Pubkey,
+    pub previous_authority: Pubkey,
+    pub authority: Pubkey,
+}
@@ -9,14 +9,102 @@
 //! This program creates a registry that stores the addresses created and thus
 //! can be queried more efficiently.
 //!
-//! Note: Nothing prevents a registry from having duplicate entries as the
-//! address lookup program does not enforce uniqueness.
-//! Thus callers (libraries) should enforce this to prevent wasting lamports
-//! storing addresses that have duplicates.
+//! The registry account is the authority of the lookup tables it creates, so
+//! that the tables move along with the registry when its authority is transferred.
+//!
+//! Note: The address lookup program does not enforce uniqueness, so a registry
+//! can have duplicate entries. Callers can opt into deduplication when appending
+//! to a lookup table, which drops the addresses that are already in the table
+//! or in other active tables of the registry page that are passed along, see
+//! [lookup_table_registry::append_to_lookup_table].
 //!
 //! Possible use-cases:
 //! - A wallet or margin account can store the ATAs owned by it, or those of common tokens.
 //! - A program can store the addresses used in a market/pool.
+//!
+//! # Program-owned registries
+//!
+//! The authority of a registry can be a PDA of another program, which signs for
+//! the registry with `invoke_signed`. The payer has to be an account that the
+//! system program can transfer from, so it's usually the user's wallet.
+//! The `cpi` feature provides the instructions and accounts to do so:
+//!
+#![cfg_attr(feature = "cpi", doc = "```no_run")]
+#![cfg_attr(not(feature = "cpi"), doc = "```ignore")]
+//! use anchor_lang::prelude::*;
+//! use lookup_table_registry::cpi::{accounts::CreateLookupTable, create_lookup_table};
+//!
+//! /// The accounts of a pool's instruction that creates a lookup table
+//! struct CreatePoolLookupTable<'info> {
+//!     pool: AccountInfo<'info>,
+//!     pool_authority: AccountInfo<'info>,
+//!     payer: AccountInfo<'info>,
+//!     registry_account: AccountInfo<'info>,
+//!     lookup_table: AccountInfo<'info>,
+//!     lookup_table_program: AccountInfo<'info>,
+//!     registry_program: AccountInfo<'info>,
+//!     system_program: AccountInfo<'info>,
+//! }
+//!
+//! fn create_pool_lookup_table(
+//!     accounts: &CreatePoolLookupTable,
+//!     pool_authority_bump: u8,
+//!     recent_slot: u64,
+//!     discriminator: u64,
+//! ) -> Result<()> {
+//!     // The pool authority's PDA signs as the authority of its registry
+//!     let pool_key = accounts.pool.key();
+//!     let signer_seeds: &[&[&[u8]]] = &[&[
+//!         b"pool-authority",
+//!         pool_key.as_ref(),
+//!         &[pool_authority_bump],
+//!     ]];
+//!     create_lookup_table(
+//!         CpiContext::new_with_signer(
+//!             accounts.registry_program.clone(),
+//!             CreateLookupTable {
+//!                 authority: accounts.pool_authority.clone(),
+//!                 payer: accounts.payer.clone(),
+//!                 registry_account: accounts.registry_account.clone(),
+//!                 lookup_table: accounts.lookup_table.clone(),
+//!                 address_lookup_table_program: accounts.lookup_table_program.clone(),
+//!                 system_program: accounts.system_program.clone(),
+//!             },
+//!             signer_seeds,
+//!         ),
+//!         recent_slot,
+//!         discriminator,
+//!     )
+//! }
+//! ```
+//!
+//! The registry and lookup table addresses are derived from the PDA as with any
+//! other authority, so clients can find the tables of a pool by its PDA.
+//!
+//! # Public registries
+//!
+//! A program can have a public registry, derived from [PUBLIC_REGISTRY_SEED]
+//! and the program ID, to share lookup tables of common addresses. Only the
+//! program's upgrade authority can initialize it, choosing whether it has a curator.
+//! The curator creates, freezes and removes its lookup tables, while anyone can
+//! extend them, paying for the addresses, until they are frozen. Without a
+//! curator, anyone can also create lookup tables, which are never removed.
+//!
+//! # Delegates
+//!
+//! The authority can add up to [MAX_REGISTRY_DELEGATES] delegates to a registry
+//! page, each of which can extend the lookup tables with a discriminator, so that
+//! a hot key can keep tables current while the authority stays cold. Delegates
+//! can't create, freeze or remove lookup tables, nor extend the tables that
+//! the authority owns.
+//!
+//! # Verifying lookup tables
+//!
+//! Programs can check that a lookup table comes from a registry with the
+//! [lookup_table_registry::verify_table] instruction, whose return data is a
+//! [TableVerification], or by reading the registry account directly with
+//! [RegistryAccount::contains_active]. Either way, the caller has to check the
+//! authority of the registry, as anyone can create a registry.
 
 #![allow(clippy::result_large_err, clippy::assertions_on_constants)]
 
@@ -25,11 +113,16 @@ use solana_address_lookup_table_program_gateway as solana_address_lookup_table_p
 
 declare_id!("LTR8xXcSrEDsCbTWPY4JmJREFdMz4uYh65uajkVjzru");
 
+mod events;
 mod state;
 
+pub use events::*;
 pub use state::*;
 
-/// Special constants for the discriminator
+/// Special constants for the discriminator.
+///
+/// Values above [discriminator::DEACTIVATED] are chosen by the registry authority
+/// to tag a lookup table by its purpose (e.g. the addresses of a pool).
 pub mod discriminator {
     /// No table is stored
     pub const EMPTY: u64 = 0b0;
@@ -49,24 +142,64 @@ pub mod lookup_table_registry {
     /// Initialize a registry account owned by the authority.
     ///
     /// Errors if a registry account already exists.
-    pub fn init_registry_account(ctx: Context<InitRegistryAccount>) -> Result<()> {
+    pub fn init_registry_account(
+        ctx: Context<InitRegistryAccount>,
+        name: Option<[u8; REGISTRY_NAME_LEN]>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Initialize the public registry of a program, whose lookup tables
+    /// anyone can extend.
+    pub fn init_public_registry(ctx: Context<InitPublicRegistry>, curator: Pubkey) -> Result<()> {
         unimplemented!()
     }
 
-    /// Create a lookup table in the registry
+    /// Create a lookup table in the registry.
+    ///
+    /// Errors if the `recent_slot` is not after the last slot used to create a table.
     pub fn create_lookup_table(
         ctx: Context<CreateLookupTable>,
         recent_slot: u64,
-        _discriminator: u64,
+        discriminator: u64,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Create a lookup table in the registry, and add the addresses to it.
+    ///
+    /// The same requirements as [create_lookup_table] apply.
+    pub fn create_and_extend_lookup_table<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateLookupTable<'info>>,
+        recent_slot: u64,
+        discriminator: u64,
+        addresses: Vec<Pubkey>,
     ) -> Result<()> {
         unimplemented!()
     }
 
+    /// Create a lookup table in the registry for each of the recent slots.
+    ///
+    /// Returns the addresses of the lookup tables.
+    pub fn create_lookup_tables<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateLookupTables<'info>>,
+        recent_slots: Vec<u64>,
+        discriminator: u64,
+    ) -> Result<Vec<Pubkey>> {
+        unimplemented!()
+    }
+
+    /// Add a lookup table that was created outside of the registry.
+    pub fn import_lookup_table(ctx: Context<ImportLookupTable>, discriminator: u64) -> Result<()> {
+        unimplemented!()
+    }
+
     /// Add addresses to a lookup table.
     pub fn append_to_lookup_table(
         ctx: Context<AppendToLookupTable>,
         addresses: Vec<Pubkey>,
-        _discriminator: u64,
+        discriminator: u64,
+        dedup: bool,
     ) -> Result<()> {
         unimplemented!()
     }
@@ -76,81 +209,220 @@ pub mod lookup_table_registry {
     pub fn remove_lookup_table(ctx: Context<RemoveLookupTable>) -> Result<()> {
         unimplemented!()
     }
+
+    /// Close a deactivated lookup table once its cooldown has elapsed, which
+    /// anyone can do, returning its lamports to the registry's recipient.
+    pub fn close_lookup_table(ctx: Context<CloseLookupTable>) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Set the recipient of the rent of lookup tables that anyone can close.
+    pub fn set_recipient(ctx: Context<SetRecipient>, recipient: Pubkey) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Allow a delegate to extend the lookup tables with a discriminator.
+    pub fn add_delegate(
+        ctx: Context<ManageDelegate>,
+        delegate: Pubkey,
+        discriminator: u64,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Revoke a delegate's access to the lookup tables with a discriminator.
+    pub fn remove_delegate(
+        ctx: Context<ManageDelegate>,
+        delegate: Pubkey,
+        discriminator: u64,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Remove a lookup table owned by the authority from the registry without closing it.
+    pub fn detach_lookup_table(ctx: Context<DetachLookupTable>) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Freeze a lookup table, making it immutable.
+    pub fn freeze_lookup_table(ctx: Context<FreezeLookupTable>) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Set the label and metadata of a lookup table's entry.
+    pub fn set_entry_label(
+        ctx: Context<SetEntryLabel>,
+        label: [u8; ENTRY_LABEL_LEN],
+        metadata: [u8; ENTRY_METADATA_LEN],
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Propose a new authority for the registry account.
+    pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Accept the authority of a registry account proposed by its current authority.
+    pub fn accept_authority<'info>(
+        ctx: Context<'_, '_, '_, 'info, AcceptAuthority<'info>>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Close a registry account, returning its lamports to the recipient.
+    pub fn close_registry_account<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseRegistryAccount<'info>>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Add a page to the registry to store more lookup tables.
+    pub fn init_registry_page(ctx: Context<InitRegistryPage>) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Close the last page of a registry, returning its lamports to the recipient.
+    pub fn close_registry_page<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseRegistryPage<'info>>,
+    ) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Migrate a registry account to the latest layout version.
+    pub fn migrate_registry(ctx: Context<MigrateRegistry>) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Remove empty entries from a registry page, and shrink it to refund rent.
+    pub fn compact_registry(ctx: Context<CompactRegistry>, reorder: bool) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Recompute the counters of a registry page from its entries.
+    pub fn repair_registry(ctx: Context<RepairRegistry>) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Report the membership of a lookup table in a registry page.
+    pub fn verify_table(ctx: Context<VerifyTable>) -> Result<TableVerification> {
+        unimplemented!()
+    }
 }
 
 /// Lookup table registry program
 #[cfg_attr(feature = "program", program)]
 #[cfg(feature = "program")]
 pub mod lookup_table_registry {
-    use solana_program::program::invoke;
+    use solana_program::{entrypoint::MAX_PERMITTED_DATA_INCREASE, program::invoke_signed};
 
     use super::*;
 
     /// Initialize a registry account owned by the authority.
     ///
+    /// An authority has a registry derived from its key alone, and can have
+    /// further registries derived from its key and a name, such as one per
+    /// environment. A name of all zeros is the same as no name.
+    ///
     /// Errors if a registry account already exists.
-    pub fn init_registry_account(ctx: Context<InitRegistryAccount>) -> Result<()> {
+    pub fn init_registry_account(
+        ctx: Context<InitRegistryAccount>,
+        name: Option<[u8; REGISTRY_NAME_LEN]>,
+    ) -> Result<()> {
         let clock = Clock::get()?;
-        let registry = &mut ctx.accounts.registry_account;
+        let mut registry = ctx.accounts.registry_account.load_init()?;
+        registry.name = name.unwrap_or_default();
         registry.authority = ctx.accounts.authority.key();
-        registry.version = 0;
+        registry.seed_authority = ctx.accounts.authority.key();
+        registry.pending_authority = Pubkey::default();
+        registry.version = version::LATEST;
+        registry.len = 0;
+        registry.capacity = 0;
+        registry.page = 0;
+        registry.page_count = 1;
+        registry.last_created_slot = clock.slot;
+        registry.seed = [*ctx.bumps.get("registry_account").unwrap()];
+
+        emit!(RegistryInitialized {
+            registry: ctx.accounts.registry_account.key(),
+            authority: registry.authority,
+        });
+
+        Ok(())
+    }
+
+    /// Initialize the public registry of a program, whose lookup tables
+    /// anyone can extend.
+    ///
+    /// The upgrade authority of the program has to sign, so that only the
+    /// program's owner decides whether the registry has a curator.
+    ///
+    /// The curator can freeze and remove the lookup tables of the registry,
+    /// and create its lookup tables. It has to be the upgrade authority of
+    /// the program. A registry initialized with the default pubkey as its
+    /// curator can never be curated, and anyone can create its lookup tables.
+    pub fn init_public_registry(ctx: Context<InitPublicRegistry>, curator: Pubkey) -> Result<()> {
+        let program = ctx.accounts.program.key();
+        if curator != Pubkey::default() && curator != ctx.accounts.upgrade_authority.key() {
+            msg!("The curator has to be the upgrade authority of the program");
+            return err!(ErrorCode::InvalidAuthority);
+        }
+
+        let clock = Clock::get()?;
+        let mut registry = ctx.accounts.registry_account.load_init()?;
+        registry.authority = curator;
+        registry.seed_authority = program;
+        registry.pending_authority = Pubkey::default();
+        registry.version = version::LATEST;
         registry.len = 0;
         registry.capacity = 0;
+        registry.page = 0;
+        registry.page_count = 1;
+        registry.public = 1;
         registry.last_created_slot = clock.slot;
         registry.seed = [*ctx.bumps.get("registry_account").unwrap()];
-        registry.tables = vec![];
+
+        emit!(PublicRegistryInitialized {
+            registry: ctx.accounts.registry_account.key(),
+            program,
+            curator,
+        });
 
         Ok(())
     }
 
-    /// Create a lookup table in the registry
+    /// Create a lookup table in the registry.
+    ///
+    /// Errors if the `recent_slot` is not after the last slot used to create a table.
     pub fn create_lookup_table(
         ctx: Context<CreateLookupTable>,
         recent_slot: u64,
-        _discriminator: u64,
+        discriminator: u64,
     ) -> Result<()> {
-        if ctx.accounts.registry_account.len as usize == MAX_REGISTRY_ENTRIES {
-            return err!(ErrorCode::TooManyEntries);
-        }
-        let discriminator = discriminator::DEACTIVATED + 1;
-        // Discriminator can't be 0
-        if discriminator <= discriminator::DEACTIVATED {
-            return err!(ErrorCode::InvalidDiscriminator);
-        }
-        ctx.accounts.registry_account.last_created_slot = recent_slot;
-        // Allocate space on the registry account if there are no more slots
-        let (len, capacity) = {
-            let registry = &ctx.accounts.registry_account;
-            (registry.len, registry.capacity)
+        let registry = {
+            let (mut registry, tables) = ctx.accounts.registry_account.load_page_mut()?;
+            if tables.len() >= MAX_REGISTRY_ENTRIES && tables.empty_entries() == 0 {
+                return err!(ErrorCode::TooManyEntries);
+            }
+            check_discriminator(discriminator)?;
+            // Lookup table addresses are derived from the slot, thus a slot can only
+            // be used once.
+            if recent_slot <= registry.last_created_slot {
+                msg!(
+                    "Slot {} has to be after the last created slot {}",
+                    recent_slot,
+                    registry.last_created_slot
+                );
+                return err!(ErrorCode::InvalidSlot);
+            }
+            registry.last_created_slot = recent_slot;
+            *registry
         };
-        let registry_info = ctx.accounts.registry_account.to_account_info();
-        let append_to_end = len == capacity;
-        if append_to_end {
-            // Needs realloc
-            let new_size = registry_info.data_len() + REGISTRY_ENTRY_SIZE;
-            let rent = Rent::get()?;
-            let transfer_amount = rent
-                .minimum_balance(new_size)
-                .checked_sub(registry_info.lamports())
-                .unwrap();
-            anchor_lang::system_program::transfer(
-                CpiContext::new(
-                    ctx.accounts.system_program.to_account_info(),
-                    anchor_lang::system_program::Transfer {
-                        from: ctx.accounts.payer.to_account_info(),
-                        to: registry_info,
-                    },
-                ),
-                transfer_amount,
-            )?;
-            // Increment the length of the registry
-            ctx.accounts.registry_account.len += 1;
-        }
 
-        // Create the lookup table
+        // Create the lookup table, with the registry account as its authority
         let (lookup_instruction, table) =
             solana_address_lookup_table_program::instruction::create_lookup_table_signed(
-                ctx.accounts.authority.key(),
+                ctx.accounts.registry_account.key(),
                 ctx.accounts.payer.key(),
                 recent_slot,
             );
@@ -158,190 +430,1368 @@ pub mod lookup_table_registry {
             return err!(ErrorCode::InvalidLookupTable);
         }
 
-        invoke(
+        invoke_signed(
             &lookup_instruction,
             &[
                 ctx.accounts.lookup_table.to_account_info(),
-                ctx.accounts.authority.to_account_info(),
+                ctx.accounts.registry_account.to_account_info(),
                 ctx.accounts.payer.to_account_info(),
                 ctx.accounts.system_program.to_account_info(),
                 ctx.accounts.address_lookup_table_program.to_account_info(),
             ],
+            &[&registry.signer_seeds()],
         )?;
 
-        // Add the account to the lookup registry
-        let entry = RegistryEntry {
-            discriminator,
+        // Add the account to the lookup registry, reusing an empty entry or
+        // allocating space for a new one
+        let entry = RegistryEntry::new(discriminator, table, Clock::get()?.slot);
+        add_entry(
+            &ctx.accounts.registry_account,
+            entry,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+        )?;
+
+        emit!(LookupTableCreated {
+            registry: ctx.accounts.registry_account.key(),
             table,
+            discriminator,
+            recent_slot,
+        });
+
+        Ok(())
+    }
+
+    /// Create a lookup table in the registry, and add the addresses to it.
+    ///
+    /// The same requirements as [create_lookup_table] apply.
+    pub fn create_and_extend_lookup_table<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateLookupTable<'info>>,
+        recent_slot: u64,
+        discriminator: u64,
+        addresses: Vec<Pubkey>,
+    ) -> Result<()> {
+        create_lookup_table(
+            Context::new(
+                ctx.program_id,
+                &mut *ctx.accounts,
+                ctx.remaining_accounts,
+                ctx.bumps.clone(),
+            ),
+            recent_slot,
+            discriminator,
+        )?;
+        if addresses.is_empty() {
+            return Ok(());
+        }
+
+        // The new table is owned by the registry account
+        let registry = *ctx.accounts.registry_account.load()?;
+        let count = addresses.len() as u32;
+        let instruction = solana_address_lookup_table_program::instruction::extend_lookup_table(
+            ctx.accounts.lookup_table.key(),
+            ctx.accounts.registry_account.key(),
+            Some(ctx.accounts.payer.key()),
+            addresses,
+        );
+
+        invoke_signed(
+            &instruction,
+            &[
+                ctx.accounts.lookup_table.to_account_info(),
+                ctx.accounts.registry_account.to_account_info(),
+                ctx.accounts.payer.to_account_info(),
+                ctx.accounts.system_program.to_account_info(),
+                ctx.accounts.address_lookup_table_program.to_account_info(),
+            ],
+            &[&registry.signer_seeds()],
+        )?;
+
+        emit!(LookupTableExtended {
+            registry: ctx.accounts.registry_account.key(),
+            table: ctx.accounts.lookup_table.key(),
+            count,
+        });
+
+        Ok(())
+    }
+
+    /// Create a lookup table in the registry for each of the recent slots.
+    ///
+    /// The slots have to be in ascending order, and after the last slot used to
+    /// create a table. The lookup tables are passed as remaining accounts in the
+    /// same order as their slots. Up to [MAX_CREATE_LOOKUP_TABLES] tables can
+    /// be created at once.
+    ///
+    /// Returns the addresses of the lookup tables.
+    pub fn create_lookup_tables<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateLookupTables<'info>>,
+        recent_slots: Vec<u64>,
+        discriminator: u64,
+    ) -> Result<Vec<Pubkey>> {
+        let count = recent_slots.len();
+        if count > MAX_CREATE_LOOKUP_TABLES {
+            msg!(
+                "Can create up to {} lookup tables at once",
+                MAX_CREATE_LOOKUP_TABLES
+            );
+            return err!(ErrorCode::InvalidLookupTable);
+        }
+        if count == 0 || count != ctx.remaining_accounts.len() {
+            msg!(
+                "Expected a lookup table for each of the {} slots, got {}",
+                count,
+                ctx.remaining_accounts.len()
+            );
+            return err!(ErrorCode::InvalidLookupTable);
+        }
+        check_discriminator(discriminator)?;
+        let (registry, appended) = {
+            let (mut registry, tables) = ctx.accounts.registry_account.load_page_mut()?;
+            // Each slot can only be used once
+            let mut last_created_slot = registry.last_created_slot;
+            for recent_slot in &recent_slots {
+                if *recent_slot <= last_created_slot {
+                    msg!(
+                        "Slot {} has to be after the last created slot {}",
+                        recent_slot,
+                        last_created_slot
+                    );
+                    return err!(ErrorCode::InvalidSlot);
+                }
+                last_created_slot = *recent_slot;
+            }
+            registry.last_created_slot = last_created_slot;
+
+            // Reuse the empty entries, and grow the registry for the rest
+            let appended = count.saturating_sub(tables.empty_entries());
+            if tables.len() + appended > MAX_REGISTRY_ENTRIES {
+                return err!(ErrorCode::TooManyEntries);
+            }
+            registry.capacity += appended as u8;
+            (*registry, appended)
         };
-        if append_to_end {
-            // Happy case, add to the end
-            let registry_info = ctx.accounts.registry_account.to_account_info();
-            let existing_len = registry_info.data_len();
-            registry_info.realloc(existing_len + REGISTRY_ENTRY_SIZE, true)?;
-            ctx.accounts.registry_account.tables.push(entry);
-        } else {
-            // Find a slot that's empty
-            let slot = ctx.accounts.registry_account.find_empty_entry()?;
-            *slot = entry;
+        if appended > 0 {
+            grow_registry(
+                &ctx.accounts.registry_account,
+                appended,
+                &ctx.accounts.payer,
+                &ctx.accounts.system_program,
+            )?;
         }
-        ctx.accounts.registry_account.capacity += 1;
-        // Redundant check
-        if ctx.accounts.registry_account.len > ctx.accounts.registry_account.capacity {
-            return err!(ErrorCode::InvalidState);
+
+        let clock = Clock::get()?;
+        let mut tables = Vec::with_capacity(count);
+        for (recent_slot, lookup_table) in recent_slots.into_iter().zip(ctx.remaining_accounts) {
+            // Create the lookup table, with the registry account as its authority
+            let (lookup_instruction, table) =
+                solana_address_lookup_table_program::instruction::create_lookup_table_signed(
+                    ctx.accounts.registry_account.key(),
+                    ctx.accounts.payer.key(),
+                    recent_slot,
+                );
+            if table != lookup_table.key() {
+                return err!(ErrorCode::InvalidLookupTable);
+            }
+
+            invoke_signed(
+                &lookup_instruction,
+                &[
+                    lookup_table.clone(),
+                    ctx.accounts.registry_account.to_account_info(),
+                    ctx.accounts.payer.to_account_info(),
+                    ctx.accounts.system_program.to_account_info(),
+                    ctx.accounts.address_lookup_table_program.to_account_info(),
+                ],
+                &[&registry.signer_seeds()],
+            )?;
+
+            // Add the account to the lookup registry, the registry was grown
+            // with empty entries
+            let entry = RegistryEntry::new(discriminator, table, clock.slot);
+            let (mut registry, mut entries) = ctx.accounts.registry_account.load_page_mut()?;
+            *entries.find_empty_entry()? = entry;
+            registry.len += 1;
+            tables.push(table);
+
+            emit!(LookupTableCreated {
+                registry: ctx.accounts.registry_account.key(),
+                table,
+                discriminator,
+                recent_slot,
+            });
         }
 
+        Ok(tables)
+    }
+
+    /// Add a lookup table that was created outside of the registry.
+    ///
+    /// The lookup table's authority has to be the registry authority, in which
+    /// case the table remains owned by the authority, or the registry account.
+    /// Deactivated and frozen lookup tables can't be imported, nor tables owned
+    /// by the authority while a new authority is pending, see [propose_authority].
+    pub fn import_lookup_table(ctx: Context<ImportLookupTable>, discriminator: u64) -> Result<()> {
+        check_discriminator(discriminator)?;
+        let table = ctx.accounts.lookup_table.key();
+        let registry = {
+            let (registry, tables) = ctx.accounts.registry_account.load_page()?;
+            if tables.find_entry(&table).is_ok() {
+                msg!("Lookup table {} is already in the registry", table);
+                return err!(ErrorCode::InvalidLookupTable);
+            }
+            *registry
+        };
+        let legacy_authority = {
+            let data = ctx.accounts.lookup_table.try_borrow_data()?;
+            let lookup_table =
+                solana_address_lookup_table_program::state::AddressLookupTable::deserialize(&data)
+                    .map_err(|_| error!(ErrorCode::InvalidLookupTable))?;
+            if lookup_table.meta.deactivation_slot != u64::MAX {
+                msg!("Cannot import a lookup table that is deactivated");
+                return err!(ErrorCode::InvalidLookupTable);
+            }
+            match lookup_table.meta.authority {
+                // The table couldn't move with a registry that is being transferred
+                Some(authority) if authority == registry.authority => {
+                    if registry.pending_authority != Pubkey::default() {
+                        msg!("Cannot import a table owned by the authority during a transfer");
+                        return err!(ErrorCode::LegacyLookupTables);
+                    }
+                    true
+                }
+                Some(authority) if authority == ctx.accounts.registry_account.key() => false,
+                _ => {
+                    msg!("The lookup table's authority is not the registry's");
+                    return err!(ErrorCode::InvalidAuthority);
+                }
+            }
+        };
+
+        // The creation slot of the table is unknown
+        let entry = RegistryEntry {
+            legacy_authority: legacy_authority as u8,
+            ..RegistryEntry::new(discriminator, table, 0)
+        };
+        add_entry(
+            &ctx.accounts.registry_account,
+            entry,
+            &ctx.accounts.payer,
+            &ctx.accounts.system_program,
+        )?;
+
+        emit!(LookupTableImported {
+            registry: ctx.accounts.registry_account.key(),
+            table,
+            discriminator,
+        });
+
         Ok(())
     }
 
     /// Add addresses to a lookup table.
+    ///
+    /// If `dedup` is set, addresses that are already in the lookup table, or in
+    /// any of the active lookup tables of the registry page passed as remaining
+    /// accounts, are dropped along with repeated addresses. Nothing is appended
+    /// if no addresses remain, so that writers racing to append the same
+    /// addresses don't fail.
     pub fn append_to_lookup_table(
         ctx: Context<AppendToLookupTable>,
         addresses: Vec<Pubkey>,
-        _discriminator: u64,
+        discriminator: u64,
+        dedup: bool,
     ) -> Result<()> {
         // Find the table in the registry
-        {
-            let entry = ctx
-                .accounts
-                .registry_account
-                .find_entry(ctx.accounts.lookup_table.key)?;
+        let (registry, table_authority) = {
+            let (registry, tables) = ctx.accounts.registry_account.load_page()?;
+            let entry = tables.find_entry(ctx.accounts.lookup_table.key)?;
 
             if entry.discriminator <= crate::discriminator::DEACTIVATED {
                 msg!("Cannot append to a lookup table that is deactivated");
                 return err!(ErrorCode::InvalidDiscriminator);
             }
-            // The discriminators should be compared in future versions
+            if entry.discriminator != discriminator {
+                msg!(
+                    "Discriminator {} does not match the entry's discriminator {}",
+                    discriminator,
+                    entry.discriminator
+                );
+                return err!(ErrorCode::InvalidDiscriminator);
+            }
+            if entry.is_frozen() {
+                return err!(ErrorCode::LookupTableFrozen);
+            }
+            // Only the authority can sign for the tables it owns
+            if entry.is_legacy_authority() && registry.authority != ctx.accounts.authority.key() {
+                return err!(ErrorCode::InvalidAuthority);
+            }
+            (
+                *registry,
+                table_authority(
+                    entry.is_legacy_authority(),
+                    &ctx.accounts.registry_account,
+                    &ctx.accounts.authority,
+                ),
+            )
+        };
+        let addresses = if dedup {
+            let (_, tables) = ctx.accounts.registry_account.load_page()?;
+            dedup_addresses(
+                addresses,
+                &tables,
+                &ctx.accounts.lookup_table,
+                ctx.remaining_accounts,
+            )?
+        } else {
+            addresses
+        };
+        if dedup && addresses.is_empty() {
+            msg!("All addresses are already in the lookup tables");
+            return Ok(());
         }
 
+        let count = addresses.len() as u32;
         let instruction = solana_address_lookup_table_program::instruction::extend_lookup_table(
             ctx.accounts.lookup_table.key(),
-            ctx.accounts.authority.key(),
+            table_authority.key(),
             Some(ctx.accounts.payer.key()),
             addresses,
         );
 
-        invoke(
+        invoke_signed(
             &instruction,
             &[
                 ctx.accounts.lookup_table.to_account_info(),
-                ctx.accounts.authority.to_account_info(),
+                table_authority,
                 ctx.accounts.payer.to_account_info(),
                 ctx.accounts.system_program.to_account_info(),
                 ctx.accounts.address_lookup_table_program.to_account_info(),
             ],
+            &[&registry.signer_seeds()],
         )?;
 
+        emit!(LookupTableExtended {
+            registry: ctx.accounts.registry_account.key(),
+            table: ctx.accounts.lookup_table.key(),
+            count,
+        });
+
         Ok(())
     }
 
     /// Remove a lookup table by either deactivating or deleting it depending on its
     /// current status.
+    ///
+    /// The slot of the deactivation is recorded, see [RegistryEntry::closeable_after_slot].
     pub fn remove_lookup_table(ctx: Context<RemoveLookupTable>) -> Result<()> {
-        // Find the table in the registry
-        let entry = ctx
-            .accounts
-            .registry_account
-            .find_entry_mut(ctx.accounts.lookup_table.key)?;
-        // If the entry is active, deactivate it
-        let to_delete = match entry.discriminator {
-            discriminator::EMPTY => {
-                msg!("Found an entry with an EMPTY discriminator, invalid state");
-                return err!(ErrorCode::InvalidState);
-            }
-            discriminator::DEACTIVATED => {
-                // mark as closed
-                entry.discriminator = discriminator::EMPTY;
-                entry.table = Pubkey::default();
-                // Decrement the registry length
-                ctx.accounts.registry_account.len =
-                    ctx.accounts.registry_account.len.checked_sub(1).unwrap();
-                true
-            }
-            _ => {
-                // mark as deactivated
-                entry.discriminator = discriminator::DEACTIVATED;
-                false
+        let clock = Clock::get()?;
+        let (registry, legacy_authority, to_delete) = {
+            let (mut registry, mut tables) = ctx.accounts.registry_account.load_page_mut()?;
+            // Find the table in the registry
+            let entry = tables.find_entry_mut(ctx.accounts.lookup_table.key)?;
+            // Frozen tables can't be deactivated
+            if entry.is_frozen() {
+                return err!(ErrorCode::LookupTableFrozen);
             }
+            let legacy_authority = entry.is_legacy_authority();
+            // If the entry is active, deactivate it
+            let to_delete = match entry.discriminator {
+                discriminator::EMPTY => {
+                    msg!("Found an entry with an EMPTY discriminator, invalid state");
+                    return err!(ErrorCode::InvalidState);
+                }
+                discriminator::DEACTIVATED => {
+                    // mark as closed
+                    entry.discriminator = discriminator::EMPTY;
+                    entry.table = Pubkey::default();
+                    // Decrement the registry length
+                    registry.len = registry.len.checked_sub(1).unwrap();
+                    true
+                }
+                _ => {
+                    // mark as deactivated
+                    entry.discriminator = discriminator::DEACTIVATED;
+                    entry.deactivated_slot = clock.slot;
+                    false
+                }
+            };
+            (*registry, legacy_authority, to_delete)
         };
+        let table_authority = table_authority(
+            legacy_authority,
+            &ctx.accounts.registry_account,
+            &ctx.accounts.authority,
+        );
 
         if to_delete {
             // Close the lookup table
             let lookup_instruction =
                 solana_address_lookup_table_program::instruction::close_lookup_table(
                     ctx.accounts.lookup_table.key(),
-                    ctx.accounts.authority.key(),
+                    table_authority.key(),
                     ctx.accounts.recipient.key(),
                 );
 
-            invoke(
+            invoke_signed(
                 &lookup_instruction,
                 &[
                     ctx.accounts.lookup_table.to_account_info(),
-                    ctx.accounts.authority.to_account_info(),
+                    table_authority,
                     ctx.accounts.recipient.to_account_info(),
                     ctx.accounts.system_program.to_account_info(),
                     ctx.accounts.address_lookup_table_program.to_account_info(),
                 ],
+                &[&registry.signer_seeds()],
             )?;
+
+            emit!(LookupTableClosed {
+                registry: ctx.accounts.registry_account.key(),
+                table: ctx.accounts.lookup_table.key(),
+                recipient: ctx.accounts.recipient.key(),
+            });
         } else {
             // Deactivate the lookup table
             let lookup_instruction =
                 solana_address_lookup_table_program::instruction::deactivate_lookup_table(
                     ctx.accounts.lookup_table.key(),
-                    ctx.accounts.authority.key(),
+                    table_authority.key(),
                 );
 
-            invoke(
+            invoke_signed(
                 &lookup_instruction,
                 &[
                     ctx.accounts.lookup_table.to_account_info(),
-                    ctx.accounts.authority.to_account_info(),
+                    table_authority,
                     ctx.accounts.address_lookup_table_program.to_account_info(),
                 ],
+                &[&registry.signer_seeds()],
             )?;
+
+            emit!(LookupTableDeactivated {
+                registry: ctx.accounts.registry_account.key(),
+                table: ctx.accounts.lookup_table.key(),
+                slot: clock.slot,
+            });
         }
 
         Ok(())
     }
-}
-
-/// Accounts for the instruction to initialize a lookup table registry account
-#[derive(Accounts)]
-pub struct InitRegistryAccount<'info> {
-    /// The authority of the registry account
-    pub authority: Signer<'info>,
 
-    /// The payer of the transaction
-    #[account(mut)]
-    pub payer: Signer<'info>,
+    /// Close a deactivated lookup table once its cooldown has elapsed, which
+    /// anyone can do, returning its lamports to the registry's recipient.
+    ///
+    /// The registry page has to have a recipient, see [set_recipient]. Lookup
+    /// tables owned by the authority can only be closed by the authority.
+    pub fn close_lookup_table(ctx: Context<CloseLookupTable>) -> Result<()> {
+        let clock = Clock::get()?;
+        let registry = {
+            let (mut registry, mut tables) = ctx.accounts.registry_account.load_page_mut()?;
+            let entry = tables.find_entry_mut(ctx.accounts.lookup_table.key)?;
+            if entry.discriminator != discriminator::DEACTIVATED {
+                msg!("Only deactivated lookup tables can be closed");
+                return err!(ErrorCode::InvalidDiscriminator);
+            }
+            if entry.is_legacy_authority() {
+                msg!("Lookup tables owned by the authority have to be removed by it");
+                return err!(ErrorCode::InvalidAuthority);
+            }
+            check_cooldown(entry, &ctx.accounts.lookup_table, clock.slot)?;
+            *entry = RegistryEntry::new(discriminator::EMPTY, Pubkey::default(), 0);
+            registry.len = registry.len.checked_sub(1).unwrap();
+            *registry
+        };
 
-    /// The registry account of the authority
-    #[account(init,
-        seeds = [authority.key.as_ref()],
-        bump,
-        payer = payer,
-        space = 8 + std::mem::size_of::<RegistryAccount>())
-    ]
-    pub registry_account: Box<Account<'info, RegistryAccount>>,
+        let lookup_instruction =
+            solana_address_lookup_table_program::instruction::close_lookup_table(
+                ctx.accounts.lookup_table.key(),
+                ctx.accounts.registry_account.key(),
+                ctx.accounts.recipient.key(),
+            );
 
-    /// The system program
-    pub system_program: Program<'info, System>,
-}
+        invoke_signed(
+            &lookup_instruction,
+            &[
+                ctx.accounts.lookup_table.to_account_info(),
+                ctx.accounts.registry_account.to_account_info(),
+                ctx.accounts.recipient.to_account_info(),
+                ctx.accounts.address_lookup_table_program.to_account_info(),
+            ],
+            &[&registry.signer_seeds()],
+        )?;
 
-/// Accounts for the instruction to create a lookup table in the registry
-#[derive(Accounts)]
-pub struct CreateLookupTable<'info> {
-    /// The authority of the registry account
-    pub authority: Signer<'info>,
+        emit!(LookupTableClosed {
+            registry: ctx.accounts.registry_account.key(),
+            table: ctx.accounts.lookup_table.key(),
+            recipient: ctx.accounts.recipient.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Set the recipient of the rent of lookup tables that anyone can close.
+    ///
+    /// The recipient is set on the registry page, and is inherited by new pages
+    /// when set on the first page. The default pubkey disables closing lookup
+    /// tables without the authority.
+    pub fn set_recipient(ctx: Context<SetRecipient>, recipient: Pubkey) -> Result<()> {
+        ctx.accounts.registry_account.load_mut()?.recipient = recipient;
+
+        emit!(RecipientSet {
+            registry: ctx.accounts.registry_account.key(),
+            recipient,
+        });
+
+        Ok(())
+    }
+
+    /// Allow a delegate to extend the lookup tables with a discriminator.
+    ///
+    /// Delegates are set on the registry page, and are inherited by new pages
+    /// when set on the first page. A delegate can be added for multiple
+    /// discriminators, each of which takes one of the page's
+    /// [MAX_REGISTRY_DELEGATES] slots.
+    pub fn add_delegate(
+        ctx: Context<ManageDelegate>,
+        delegate: Pubkey,
+        discriminator: u64,
+    ) -> Result<()> {
+        check_discriminator(discriminator)?;
+        if delegate == Pubkey::default() {
+            return err!(ErrorCode::InvalidDelegate);
+        }
+        let mut registry = ctx.accounts.registry_account.load_mut()?;
+        if registry.is_delegate(&delegate, discriminator) {
+            msg!(
+                "Delegate is already added for discriminator {}",
+                discriminator
+            );
+            return Ok(());
+        }
+        let slot = registry
+            .delegates
+            .iter_mut()
+            .find(|entry| entry.delegate == Pubkey::default())
+            .ok_or(ErrorCode::TooManyDelegates)?;
+        *slot = RegistryDelegate {
+            delegate,
+            discriminator,
+        };
+
+        emit!(DelegateAdded {
+            registry: ctx.accounts.registry_account.key(),
+            delegate,
+            discriminator,
+        });
+
+        Ok(())
+    }
+
+    /// Revoke a delegate's access to the lookup tables with a discriminator.
+    pub fn remove_delegate(
+        ctx: Context<ManageDelegate>,
+        delegate: Pubkey,
+        discriminator: u64,
+    ) -> Result<()> {
+        let mut registry = ctx.accounts.registry_account.load_mut()?;
+        let slot = registry
+            .delegates
+            .iter_mut()
+            .find(|entry| entry.delegate == delegate && entry.discriminator == discriminator)
+            .ok_or(ErrorCode::InvalidDelegate)?;
+        *slot = RegistryDelegate::default();
+
+        emit!(DelegateRemoved {
+            registry: ctx.accounts.registry_account.key(),
+            delegate,
+            discriminator,
+        });
+
+        Ok(())
+    }
+
+    /// Remove a lookup table owned by the authority from the registry without
+    /// closing it, so that the authority keeps managing it directly.
+    ///
+    /// Tables owned by the registry account can't be detached, as nothing but
+    /// the registry could manage them, and have to be removed instead.
+    pub fn detach_lookup_table(ctx: Context<DetachLookupTable>) -> Result<()> {
+        let (mut registry, mut tables) = ctx.accounts.registry_account.load_page_mut()?;
+        let entry = tables.find_entry_mut(ctx.accounts.lookup_table.key)?;
+        match entry.discriminator {
+            discriminator::EMPTY => return err!(ErrorCode::InvalidLookupTable),
+            discriminator::DEACTIVATED => {
+                msg!("Deactivated lookup tables have to be closed");
+                return err!(ErrorCode::InvalidDiscriminator);
+            }
+            _ => {}
+        }
+        if !entry.is_legacy_authority() {
+            msg!("Only lookup tables owned by the authority can be detached");
+            return err!(ErrorCode::InvalidAuthority);
+        }
+        *entry = RegistryEntry::new(discriminator::EMPTY, Pubkey::default(), 0);
+        registry.len = registry.len.checked_sub(1).unwrap();
+
+        emit!(LookupTableDetached {
+            registry: ctx.accounts.registry_account.key(),
+            table: ctx.accounts.lookup_table.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Freeze a lookup table, making it immutable.
+    ///
+    /// A frozen table can never be extended, deactivated or closed.
+    /// Empty lookup tables cannot be frozen.
+    pub fn freeze_lookup_table(ctx: Context<FreezeLookupTable>) -> Result<()> {
+        let (registry, legacy_authority) = {
+            let (registry, mut tables) = ctx.accounts.registry_account.load_page_mut()?;
+            let entry = tables.find_entry_mut(ctx.accounts.lookup_table.key)?;
+            if entry.discriminator <= discriminator::DEACTIVATED {
+                msg!("Cannot freeze a lookup table that is deactivated");
+                return err!(ErrorCode::InvalidDiscriminator);
+            }
+            if entry.is_frozen() {
+                return err!(ErrorCode::LookupTableFrozen);
+            }
+            entry.frozen = 1;
+            (*registry, entry.is_legacy_authority())
+        };
+        let table_authority = table_authority(
+            legacy_authority,
+            &ctx.accounts.registry_account,
+            &ctx.accounts.authority,
+        );
+
+        let lookup_instruction =
+            solana_address_lookup_table_program::instruction::freeze_lookup_table(
+                ctx.accounts.lookup_table.key(),
+                table_authority.key(),
+            );
+
+        invoke_signed(
+            &lookup_instruction,
+            &[
+                ctx.accounts.lookup_table.to_account_info(),
+                table_authority,
+                ctx.accounts.address_lookup_table_program.to_account_info(),
+            ],
+            &[&registry.signer_seeds()],
+        )?;
+
+        emit!(LookupTableFrozen {
+            registry: ctx.accounts.registry_account.key(),
+            table: ctx.accounts.lookup_table.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Set the label and metadata of a lookup table's entry.
+    ///
+    /// The label is a human-readable name padded with zeros, while the
+    /// metadata is opaque to the program. Frozen tables can still be labelled.
+    pub fn set_entry_label(
+        ctx: Context<SetEntryLabel>,
+        label: [u8; ENTRY_LABEL_LEN],
+        metadata: [u8; ENTRY_METADATA_LEN],
+    ) -> Result<()> {
+        let (_, mut tables) = ctx.accounts.registry_account.load_page_mut()?;
+        let entry = tables.find_entry_mut(ctx.accounts.lookup_table.key)?;
+        if entry.discriminator == discriminator::EMPTY {
+            return err!(ErrorCode::InvalidLookupTable);
+        }
+        entry.label = label;
+        entry.metadata = metadata;
+
+        emit!(EntryLabelSet {
+            registry: ctx.accounts.registry_account.key(),
+            table: ctx.accounts.lookup_table.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Propose a new authority for the registry account.
+    ///
+    /// The new authority has to accept the registry before it takes effect.
+    /// Public registries can't be transferred, as their curator has to be
+    /// the upgrade authority of their program.
+    /// Proposing the default pubkey cancels a pending proposal.
+    pub fn propose_authority(ctx: Context<ProposeAuthority>, new_authority: Pubkey) -> Result<()> {
+        let (mut registry, tables) = ctx.accounts.registry_account.load_page_mut()?;
+        // Tables owned by the authority can't move with the registry.
+        // Further pages are checked when the authority is accepted.
+        if tables.has_legacy_tables() {
+            msg!("Lookup tables owned by the authority have to be closed first");
+            return err!(ErrorCode::LegacyLookupTables);
+        }
+        registry.pending_authority = new_authority;
+
+        emit!(AuthorityProposed {
+            registry: ctx.accounts.registry_account.key(),
+            authority: registry.authority,
+            pending_authority: new_authority,
+        });
+
+        Ok(())
+    }
+
+    /// Accept the authority of a registry account proposed by its current authority.
+    ///
+    /// The registry keeps its address, and the lookup tables it owns are then
+    /// controlled by the new authority. All further pages of the registry have
+    /// to be passed as remaining accounts in order, so that they are transferred too.
+    ///
+    /// No page can have lookup tables owned by the current authority, as these
+    /// can't move with the registry.
+    pub fn accept_authority<'info>(
+        ctx: Context<'_, '_, '_, 'info, AcceptAuthority<'info>>,
+    ) -> Result<()> {
+        let (mut registry, tables) = ctx.accounts.registry_account.load_page_mut()?;
+        if tables.has_legacy_tables() {
+            msg!("Lookup tables owned by the authority have to be closed first");
+            return err!(ErrorCode::LegacyLookupTables);
+        }
+        if ctx.remaining_accounts.len() + 1 != registry.page_count as usize {
+            msg!("Expected {} registry pages", registry.page_count);
+            return err!(ErrorCode::InvalidPage);
+        }
+        for (page_info, index) in ctx.remaining_accounts.iter().zip(1..) {
+            let page_loader = AccountLoader::<RegistryAccount>::try_from(page_info)?;
+            if !page_loader.is_latest_version() {
+                return err!(ErrorCode::InvalidVersion);
+            }
+            let (mut page, page_tables) = page_loader.load_page_mut()?;
+            if page.seed_authority != registry.seed_authority
+                || page.name != registry.name
+                || page.page != index
+            {
+                msg!(
+                    "Account {} is not page {} of the registry",
+                    page_info.key,
+                    index
+                );
+                return err!(ErrorCode::InvalidPage);
+            }
+            if page_tables.has_legacy_tables() {
+                msg!("Lookup tables of page {} are owned by the authority", index);
+                return err!(ErrorCode::LegacyLookupTables);
+            }
+            page.authority = registry.pending_authority;
+        }
+        let previous_authority = registry.authority;
+        registry.authority = registry.pending_authority;
+        registry.pending_authority = Pubkey::default();
+
+        emit!(AuthorityChanged {
+            registry: ctx.accounts.registry_account.key(),
+            previous_authority,
+            authority: registry.authority,
+        });
+
+        Ok(())
+    }
+
+    /// Close a registry account, returning its lamports to the recipient.
+    ///
+    /// All entries in the registry have to be either empty or deactivated.
+    /// Deactivated lookup tables have to be passed as remaining accounts, and
+    /// are closed in the same instruction.
+    ///
+    /// Further pages of the registry have to be closed first.
+    pub fn close_registry_account<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseRegistryAccount<'info>>,
+    ) -> Result<()> {
+        if ctx.accounts.registry_account.load()?.page_count > 1 {
+            msg!("Close the further pages of the registry first");
+            return err!(ErrorCode::RegistryNotEmpty);
+        }
+        close_deactivated_tables(
+            &ctx.accounts.registry_account,
+            &ctx.accounts.authority,
+            ctx.remaining_accounts,
+            &ctx.accounts.recipient,
+            &ctx.accounts.address_lookup_table_program,
+            &ctx.accounts.system_program,
+        )?;
+
+        emit!(RegistryClosed {
+            registry: ctx.accounts.registry_account.key(),
+            page: 0,
+            recipient: ctx.accounts.recipient.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Add a page to the registry to store more lookup tables.
+    ///
+    /// The page index is the number of pages that the registry has.
+    pub fn init_registry_page(ctx: Context<InitRegistryPage>) -> Result<()> {
+        let mut registry = ctx.accounts.registry_account.load_mut()?;
+        let index = registry.page_count;
+        registry.page_count = index.checked_add(1).ok_or(ErrorCode::TooManyPages)?;
+
+        let mut page = ctx.accounts.registry_page.load_init()?;
+        page.authority = registry.authority;
+        page.seed_authority = registry.seed_authority;
+        page.pending_authority = Pubkey::default();
+        page.version = registry.version;
+        page.len = 0;
+        page.capacity = 0;
+        page.page = index;
+        page.page_count = 0;
+        page.recipient = registry.recipient;
+        page.delegates = registry.delegates;
+        page.name = registry.name;
+        // No lookup tables have been created by the page
+        page.last_created_slot = 0;
+        page.seed = [*ctx.bumps.get("registry_page").unwrap()];
+
+        emit!(RegistryPageInitialized {
+            registry: ctx.accounts.registry_account.key(),
+            registry_page: ctx.accounts.registry_page.key(),
+            page: index,
+        });
+
+        Ok(())
+    }
+
+    /// Close the last page of a registry, returning its lamports to the recipient.
+    ///
+    /// The same requirements as [close_registry_account] apply to the entries of the page.
+    pub fn close_registry_page<'info>(
+        ctx: Context<'_, '_, '_, 'info, CloseRegistryPage<'info>>,
+    ) -> Result<()> {
+        let page = ctx.accounts.registry_page.load()?.page;
+        {
+            let mut registry = ctx.accounts.registry_account.load_mut()?;
+            if page + 1 != registry.page_count {
+                msg!("Only the last page of the registry can be closed");
+                return err!(ErrorCode::InvalidPage);
+            }
+            registry.page_count -= 1;
+        }
+
+        close_deactivated_tables(
+            &ctx.accounts.registry_page,
+            &ctx.accounts.authority,
+            ctx.remaining_accounts,
+            &ctx.accounts.recipient,
+            &ctx.accounts.address_lookup_table_program,
+            &ctx.accounts.system_program,
+        )?;
+
+        emit!(RegistryClosed {
+            registry: ctx.accounts.registry_account.key(),
+            page,
+            recipient: ctx.accounts.recipient.key(),
+        });
+
+        Ok(())
+    }
+
+    /// Migrate a registry account to the latest layout version.
+    ///
+    /// Lookup tables created before the migration remain owned by the authority,
+    /// and are marked as such in the registry. Does nothing if the registry
+    /// account is already on the latest version.
+    ///
+    /// An account can only grow by [MAX_PERMITTED_DATA_INCREASE] bytes per
+    /// instruction, so large registries are grown over multiple calls before
+    /// they are migrated. The excess rent of a registry that shrinks is
+    /// refunded to the payer. See [RegistryPage::migrate] for registries with
+    /// more than [MAX_REGISTRY_ENTRIES] entries.
+    pub fn migrate_registry(ctx: Context<MigrateRegistry>) -> Result<()> {
+        let registry_info = ctx.accounts.registry_account.to_account_info();
+        let mut registry = {
+            let data = registry_info.try_borrow_data()?;
+            RegistryPage::try_deserialize_versioned(&mut &data[..])?
+        };
+        if registry.version == version::LATEST {
+            msg!("Registry account is already on version {}", version::LATEST);
+            return Ok(());
+        }
+        if registry.authority != ctx.accounts.authority.key() {
+            return err!(ErrorCode::InvalidAuthority);
+        }
+        // The registry has to be the one derived from its authority
+        let registry_address = Pubkey::create_program_address(&registry.signer_seeds(), &crate::ID)
+            .map_err(|_| ErrorCode::InvalidState)?;
+        if registry_address != registry_info.key() {
+            return err!(ErrorCode::InvalidState);
+        }
+        registry.migrate();
+
+        // Resize the account for the new layout, which determines the number
+        // of entries of a zero-copy registry
+        let required_size = RegistryAccount::space(registry.tables.len());
+        let existing_size = registry_info.data_len();
+        if required_size > existing_size {
+            let new_size = required_size.min(existing_size + MAX_PERMITTED_DATA_INCREASE);
+            let rent = Rent::get()?;
+            let transfer_amount = rent
+                .minimum_balance(new_size)
+                .saturating_sub(registry_info.lamports());
+            if transfer_amount > 0 {
+                anchor_lang::system_program::transfer(
+                    CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        anchor_lang::system_program::Transfer {
+                            from: ctx.accounts.payer.to_account_info(),
+                            to: registry_info.clone(),
+                        },
+                    ),
+                    transfer_amount,
+                )?;
+            }
+            registry_info.realloc(new_size, true)?;
+            if new_size < required_size {
+                msg!(
+                    "Registry account grown to {} of {} bytes, migrate again to complete",
+                    new_size,
+                    required_size
+                );
+                return Ok(());
+            }
+        } else if required_size < existing_size {
+            // Refund the rent of the smaller account to the payer
+            registry_info.realloc(required_size, false)?;
+            refund_excess_rent(&registry_info, &ctx.accounts.payer)?;
+        }
+
+        {
+            let mut data = registry_info.try_borrow_mut_data()?;
+            registry.serialize_into(&mut data)?;
+        }
+
+        emit!(RegistryMigrated {
+            registry: registry_info.key(),
+            version: version::LATEST,
+        });
+
+        Ok(())
+    }
+
+    /// Remove empty entries from a registry page, and shrink it to refund rent
+    /// to the recipient.
+    ///
+    /// Only trailing empty entries are removed, unless `reorder` is set, in which
+    /// case the active and deactivated entries are moved to the front of the
+    /// page in their existing order.
+    pub fn compact_registry(ctx: Context<CompactRegistry>, reorder: bool) -> Result<()> {
+        let entries = {
+            let (mut registry, mut tables) = ctx.accounts.registry_account.load_page_mut()?;
+            let entries = tables.compact(reorder);
+            registry.update_counters(&tables[..entries]);
+            entries
+        };
+
+        // Shrink the account and refund the excess rent
+        let registry_info = ctx.accounts.registry_account.to_account_info();
+        let new_size = RegistryAccount::space(entries);
+        let mut refund = 0;
+        if new_size < registry_info.data_len() {
+            registry_info.realloc(new_size, false)?;
+            refund = refund_excess_rent(&registry_info, &ctx.accounts.recipient)?;
+        }
+
+        emit!(RegistryCompacted {
+            registry: registry_info.key(),
+            entries: entries as u8,
+            refund,
+        });
+
+        Ok(())
+    }
+
+    /// Recompute the counters of a registry page from its entries.
+    ///
+    /// The `len` of a page is the number of entries that aren't empty, and its
+    /// `capacity` the number of allocated entries. Pages that were written
+    /// before these were kept in sync can be repaired by anyone, and repairing
+    /// a page with valid counters does nothing.
+    pub fn repair_registry(ctx: Context<RepairRegistry>) -> Result<()> {
+        let (mut registry, tables) = ctx.accounts.registry_account.load_page_mut()?;
+        if registry.has_valid_counters(&tables) {
+            msg!("Registry counters are already valid");
+            return Ok(());
+        }
+        registry.update_counters(&tables);
+
+        emit!(RegistryRepaired {
+            registry: ctx.accounts.registry_account.key(),
+            len: registry.len,
+            capacity: registry.capacity,
+        });
+
+        Ok(())
+    }
+
+    /// Report the membership of a lookup table in a registry page.
+    ///
+    /// The discriminator and status of the table's entry are set as return
+    /// data, so that other programs can check the lookup tables of their
+    /// transactions with a CPI. Tables that aren't in the page are reported
+    /// as missing instead of failing.
+    pub fn verify_table(ctx: Context<VerifyTable>) -> Result<TableVerification> {
+        let (registry, tables) = ctx.accounts.registry_account.load_page()?;
+        let (discriminator, status) = match tables.find_entry(ctx.accounts.lookup_table.key) {
+            Ok(entry) if entry.discriminator == discriminator::DEACTIVATED => {
+                (entry.discriminator, TableStatus::Deactivated)
+            }
+            Ok(entry) if entry.discriminator > discriminator::DEACTIVATED => {
+                let status = if entry.is_frozen() {
+                    TableStatus::Frozen
+                } else {
+                    TableStatus::Active
+                };
+                (entry.discriminator, status)
+            }
+            _ => (discriminator::EMPTY, TableStatus::Missing),
+        };
+
+        Ok(TableVerification {
+            authority: registry.authority,
+            discriminator,
+            status,
+        })
+    }
+}
+
+/// Grow a registry page by a number of empty entries, with rent paid by the payer
+#[cfg(feature = "program")]
+fn grow_registry<'info>(
+    registry: &AccountLoader<'info, RegistryAccount>,
+    entries: usize,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let registry_info = registry.to_account_info();
+    let new_size = registry_info.data_len() + entries * REGISTRY_ENTRY_SIZE;
+    let rent = Rent::get()?;
+    let transfer_amount = rent
+        .minimum_balance(new_size)
+        .saturating_sub(registry_info.lamports());
+    anchor_lang::system_program::transfer(
+        CpiContext::new(
+            system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: payer.to_account_info(),
+                to: registry_info.clone(),
+            },
+        ),
+        transfer_amount,
+    )?;
+    registry_info.realloc(new_size, true)?;
+
+    Ok(())
+}
+
+/// Add an entry to a registry page, either in an empty entry or by growing the
+/// page with rent paid by the payer.
+#[cfg(feature = "program")]
+fn add_entry<'info>(
+    registry: &AccountLoader<'info, RegistryAccount>,
+    entry: RegistryEntry,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    {
+        let (mut header, mut tables) = registry.load_page_mut()?;
+        if let Ok(slot) = tables.find_empty_entry() {
+            *slot = entry;
+            header.len += 1;
+            return Ok(());
+        }
+        if tables.len() >= MAX_REGISTRY_ENTRIES {
+            return err!(ErrorCode::TooManyEntries);
+        }
+    }
+    grow_registry(registry, 1, payer, system_program)?;
+    let (mut header, mut tables) = registry.load_page_mut()?;
+    *tables.find_empty_entry()? = entry;
+    header.len += 1;
+    header.capacity += 1;
+
+    Ok(())
+}
+
+/// Drop the addresses that are in the lookup table or in any of the other
+/// active lookup tables, as well as repeated addresses, keeping their order.
+///
+/// The other lookup tables have to be active tables of the registry page.
+#[cfg(feature = "program")]
+fn dedup_addresses(
+    addresses: Vec<Pubkey>,
+    entries: &[RegistryEntry],
+    lookup_table: &AccountInfo,
+    other_tables: &[AccountInfo],
+) -> Result<Vec<Pubkey>> {
+    let mut existing = std::collections::BTreeSet::new();
+    extend_with_table_addresses(&mut existing, lookup_table, false)?;
+    for table_info in other_tables {
+        if !entries.contains_active(table_info.key) {
+            msg!(
+                "Lookup table {} is not active in the registry",
+                table_info.key
+            );
+            return err!(ErrorCode::InvalidLookupTable);
+        }
+        // Deactivated tables are going away, so their addresses are still needed
+        extend_with_table_addresses(&mut existing, table_info, true)?;
+    }
+
+    Ok(addresses
+        .into_iter()
+        .filter(|address| existing.insert(*address))
+        .collect())
+}
+
+/// Add the addresses of a lookup table to a set, unless `active_only` is set
+/// and the table is deactivated.
+#[cfg(feature = "program")]
+fn extend_with_table_addresses(
+    addresses: &mut std::collections::BTreeSet<Pubkey>,
+    table_info: &AccountInfo,
+    active_only: bool,
+) -> Result<()> {
+    if table_info.owner != &solana_address_lookup_table_program::ID {
+        msg!("Account {} is not a lookup table", table_info.key);
+        return err!(ErrorCode::InvalidLookupTable);
+    }
+    let data = table_info.try_borrow_data()?;
+    let table = solana_address_lookup_table_program::state::AddressLookupTable::deserialize(&data)
+        .map_err(|_| error!(ErrorCode::InvalidLookupTable))?;
+    if active_only && table.meta.deactivation_slot != u64::MAX {
+        return Ok(());
+    }
+    addresses.extend(table.addresses.iter().copied());
+
+    Ok(())
+}
+
+/// Check that a discriminator isn't one of the reserved values, which are
+/// used to track the state of entries.
+#[cfg(feature = "program")]
+fn check_discriminator(discriminator: u64) -> Result<()> {
+    if discriminator <= discriminator::DEACTIVATED {
+        msg!("Discriminator {} is reserved", discriminator);
+        return err!(ErrorCode::InvalidDiscriminator);
+    }
+
+    Ok(())
+}
+
+/// Check that the cooldown of a deactivated lookup table has elapsed at the slot.
+///
+/// The lookup table's deactivation slot is used if the entry didn't record it.
+/// The lookup table program has the final say on whether the deactivation slot
+/// has left the slot hashes.
+#[cfg(feature = "program")]
+fn check_cooldown(entry: &RegistryEntry, lookup_table: &AccountInfo, slot: u64) -> Result<()> {
+    let closeable_after_slot = match entry.closeable_after_slot() {
+        Some(slot) => slot,
+        None => {
+            let data = lookup_table.try_borrow_data()?;
+            let lookup_table =
+                solana_address_lookup_table_program::state::AddressLookupTable::deserialize(&data)
+                    .map_err(|_| error!(ErrorCode::InvalidLookupTable))?;
+            lookup_table
+                .meta
+                .deactivation_slot
+                .saturating_add(DEACTIVATION_COOLDOWN)
+        }
+    };
+    if slot <= closeable_after_slot {
+        msg!(
+            "Lookup table can be closed after slot {}",
+            closeable_after_slot
+        );
+        return err!(ErrorCode::InvalidSlot);
+    }
+
+    Ok(())
+}
+
+/// Move the lamports of an account above its rent exemption to the recipient,
+/// returning the refunded amount.
+#[cfg(feature = "program")]
+fn refund_excess_rent(account: &AccountInfo, recipient: &AccountInfo) -> Result<u64> {
+    let refund = excess_rent(&Rent::get()?, account.lamports(), account.data_len());
+    **account.try_borrow_mut_lamports()? -= refund;
+    **recipient.try_borrow_mut_lamports()? += refund;
+
+    Ok(refund)
+}
+
+/// The lamports above the rent exemption of an account of the size
+#[cfg(feature = "program")]
+fn excess_rent(rent: &Rent, lamports: u64, size: usize) -> u64 {
+    lamports.saturating_sub(rent.minimum_balance(size))
+}
+
+/// The authority of an entry's lookup table, which is the registry account
+/// unless the table was created before registries owned their tables.
+#[cfg(feature = "program")]
+fn table_authority<'info>(
+    legacy_authority: bool,
+    registry: &AccountLoader<'info, RegistryAccount>,
+    authority: &AccountInfo<'info>,
+) -> AccountInfo<'info> {
+    if legacy_authority {
+        authority.clone()
+    } else {
+        registry.to_account_info()
+    }
+}
+
+/// Close the deactivated lookup tables of a registry page, which is about to
+/// be closed. Errors if the page has any active lookup table.
+#[cfg(feature = "program")]
+fn close_deactivated_tables<'info>(
+    registry: &AccountLoader<'info, RegistryAccount>,
+    authority: &AccountInfo<'info>,
+    lookup_tables: &[AccountInfo<'info>],
+    recipient: &AccountInfo<'info>,
+    address_lookup_table_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let (header, deactivated) = {
+        let (header, tables) = registry.load_page()?;
+        let mut deactivated = vec![];
+        for entry in tables.iter() {
+            match entry.discriminator {
+                discriminator::EMPTY => continue,
+                discriminator::DEACTIVATED => {
+                    deactivated.push((entry.table, entry.is_legacy_authority()))
+                }
+                _ => {
+                    msg!("Lookup table {} is still active", entry.table);
+                    return err!(ErrorCode::RegistryNotEmpty);
+                }
+            }
+        }
+        (*header, deactivated)
+    };
+    for (table, legacy_authority) in deactivated {
+        let lookup_table = lookup_tables
+            .iter()
+            .find(|account| account.key == &table)
+            .ok_or_else(|| {
+                msg!("Deactivated lookup table {} was not provided", table);
+                error!(ErrorCode::InvalidLookupTable)
+            })?;
+
+        let table_authority = table_authority(legacy_authority, registry, authority);
+        let lookup_instruction =
+            solana_address_lookup_table_program::instruction::close_lookup_table(
+                table,
+                table_authority.key(),
+                recipient.key(),
+            );
+
+        solana_program::program::invoke_signed(
+            &lookup_instruction,
+            &[
+                lookup_table.clone(),
+                table_authority,
+                recipient.clone(),
+                system_program.clone(),
+                address_lookup_table_program.clone(),
+            ],
+            &[&header.signer_seeds()],
+        )?;
+
+        emit!(LookupTableClosed {
+            registry: registry.key(),
+            table,
+            recipient: recipient.key(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Accounts for the instruction to initialize a lookup table registry account
+#[derive(Accounts)]
+#[instruction(name: Option<[u8; REGISTRY_NAME_LEN]>)]
+pub struct InitRegistryAccount<'info> {
+    /// The authority of the registry account
+    pub authority: Signer<'info>,
 
     /// The payer of the transaction
     #[account(mut)]
     pub payer: Signer<'info>,
 
-    /// The registry account of the authority
-    #[account(mut, constraint = registry_account.authority == authority.key())]
-    pub registry_account: Box<Account<'info, RegistryAccount>>,
+    /// The registry account of the authority, with an optional name
+    #[account(init,
+        seeds = [authority.key.as_ref(), registry_name_seed(&name.unwrap_or_default())],
+        bump,
+        payer = payer,
+        space = RegistryAccount::space(0))
+    ]
+    pub registry_account: AccountLoader<'info, RegistryAccount>,
+
+    /// The system program
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for the instruction to initialize the public registry of a program
+#[derive(Accounts)]
+pub struct InitPublicRegistry<'info> {
+    /// The payer of the transaction
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The upgrade authority of the program
+    pub upgrade_authority: Signer<'info>,
+
+    /// The program that the registry is for
+    /// CHECK: only the address of the program is used
+    #[account(executable)]
+    pub program: AccountInfo<'info>,
+
+    /// The program data account of the program, which stores its upgrade authority
+    #[account(
+        seeds = [program.key.as_ref()],
+        bump,
+        seeds::program = solana_program::bpf_loader_upgradeable::ID,
+        constraint = program_data.upgrade_authority_address == Some(upgrade_authority.key())
+            @ ErrorCode::InvalidAuthority)]
+    pub program_data: Account<'info, ProgramData>,
+
+    /// The public registry account of the program
+    #[account(init,
+        seeds = [PUBLIC_REGISTRY_SEED, program.key.as_ref()],
+        bump,
+        payer = payer,
+        space = RegistryAccount::space(0))
+    ]
+    pub registry_account: AccountLoader<'info, RegistryAccount>,
+
+    /// The system program
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for the instruction to create a lookup table in the registry
+#[derive(Accounts)]
+pub struct CreateLookupTable<'info> {
+    /// The authority of the registry account
+    pub authority: Signer<'info>,
+
+    /// The payer of the transaction
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The registry account of the authority, or a public registry
+    #[account(mut,
+        constraint = registry_account.is_latest_version() @ ErrorCode::InvalidVersion,
+        constraint = registry_account.load()?.can_create(authority.key) @ ErrorCode::InvalidAuthority)]
+    pub registry_account: AccountLoader<'info, RegistryAccount>,
 
     /// The lookup table being created
     /// CHECK: the account will be validated by the lookup table program
@@ -356,19 +1806,74 @@ pub struct CreateLookupTable<'info> {
     pub system_program: Program<'info, System>,
 }
 
+/// Accounts for the instruction to create multiple lookup tables in the registry
+///
+/// The lookup tables being created are passed as remaining accounts.
+#[derive(Accounts)]
+pub struct CreateLookupTables<'info> {
+    /// The authority of the registry account
+    pub authority: Signer<'info>,
+
+    /// The payer of the transaction
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The registry account of the authority, or a public registry
+    #[account(mut,
+        constraint = registry_account.is_latest_version() @ ErrorCode::InvalidVersion,
+        constraint = registry_account.load()?.can_create(authority.key) @ ErrorCode::InvalidAuthority)]
+    pub registry_account: AccountLoader<'info, RegistryAccount>,
+
+    /// CHECK: the account will be validated by the lookup table program
+    #[account(address = solana_address_lookup_table_program::ID)]
+    pub address_lookup_table_program: AccountInfo<'info>,
+
+    /// The system program
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for the instruction to import a lookup table into the registry
+#[derive(Accounts)]
+pub struct ImportLookupTable<'info> {
+    /// The authority of the registry account
+    pub authority: Signer<'info>,
+
+    /// The payer of the transaction
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The registry page that the lookup table is added to
+    #[account(mut,
+        constraint = registry_account.is_latest_version() @ ErrorCode::InvalidVersion,
+        constraint = registry_account.load()?.authority == authority.key())]
+    pub registry_account: AccountLoader<'info, RegistryAccount>,
+
+    /// The lookup table being imported
+    /// CHECK: the account is deserialized as a lookup table
+    #[account(owner = solana_address_lookup_table_program::ID)]
+    pub lookup_table: AccountInfo<'info>,
+
+    /// The system program
+    pub system_program: Program<'info, System>,
+}
+
 /// Accounts for the instruction to append entries to a lookup table
 #[derive(Accounts)]
+#[instruction(addresses: Vec<Pubkey>, discriminator: u64)]
 pub struct AppendToLookupTable<'info> {
-    /// The authority of the registry account
+    /// The authority of the registry account, or one of its delegates for
+    /// the discriminator
     pub authority: Signer<'info>,
 
     /// The payer of the transaction
     #[account(mut)]
     pub payer: Signer<'info>,
 
-    /// The registry account of the authority
-    #[account(constraint = registry_account.authority == authority.key())]
-    pub registry_account: Box<Account<'info, RegistryAccount>>,
+    /// The registry account of the authority, or a public registry
+    #[account(
+        constraint = registry_account.is_latest_version() @ ErrorCode::InvalidVersion,
+        constraint = registry_account.load()?.can_append(authority.key, discriminator))]
+    pub registry_account: AccountLoader<'info, RegistryAccount>,
 
     /// The lookup table being created
     /// CHECK: the account will be validated by the lookup table program
@@ -390,12 +1895,16 @@ pub struct RemoveLookupTable<'info> {
     pub authority: Signer<'info>,
 
     /// The recipient of lamports
+    /// CHECK: the recipient is checked against the registry page if it has one
     #[account(mut)]
-    pub recipient: Signer<'info>,
+    pub recipient: AccountInfo<'info>,
 
     /// The registry account of the authority
-    #[account(mut, constraint = registry_account.authority == authority.key())]
-    pub registry_account: Box<Account<'info, RegistryAccount>>,
+    #[account(mut,
+        constraint = registry_account.is_latest_version() @ ErrorCode::InvalidVersion,
+        constraint = registry_account.load()?.authority == authority.key(),
+        constraint = registry_account.load()?.accepts_recipient(recipient.key) @ ErrorCode::InvalidRecipient)]
+    pub registry_account: AccountLoader<'info, RegistryAccount>,
 
     /// The lookup table being closed
     /// CHECK: the account will be validated by the lookup table program
@@ -410,6 +1919,305 @@ pub struct RemoveLookupTable<'info> {
     pub system_program: Program<'info, System>,
 }
 
+/// Accounts for the instruction to close a deactivated lookup table
+#[derive(Accounts)]
+pub struct CloseLookupTable<'info> {
+    /// The recipient of lamports, which is configured on the registry page
+    /// CHECK: the recipient is only checked against the registry page
+    #[account(mut)]
+    pub recipient: AccountInfo<'info>,
+
+    /// The registry page of the lookup table
+    #[account(mut,
+        constraint = registry_account.is_latest_version() @ ErrorCode::InvalidVersion,
+        constraint = registry_account.load()?.recipient != Pubkey::default() @ ErrorCode::InvalidRecipient,
+        constraint = registry_account.load()?.recipient == recipient.key() @ ErrorCode::InvalidRecipient)]
+    pub registry_account: AccountLoader<'info, RegistryAccount>,
+
+    /// The lookup table being closed
+    /// CHECK: the account will be validated by the lookup table program
+    #[account(mut)]
+    pub lookup_table: AccountInfo<'info>,
+
+    /// CHECK: the account will be validated by the lookup table program
+    #[account(address = solana_address_lookup_table_program::ID)]
+    pub address_lookup_table_program: AccountInfo<'info>,
+}
+
+/// Accounts for the instruction to set the recipient of a registry page
+#[derive(Accounts)]
+pub struct SetRecipient<'info> {
+    /// The authority of the registry account
+    pub authority: Signer<'info>,
+
+    /// The registry page of the authority
+    #[account(mut,
+        constraint = registry_account.is_latest_version() @ ErrorCode::InvalidVersion,
+        constraint = registry_account.load()?.authority == authority.key())]
+    pub registry_account: AccountLoader<'info, RegistryAccount>,
+}
+
+/// Accounts for the instructions to add and remove delegates of a registry page
+#[derive(Accounts)]
+pub struct ManageDelegate<'info> {
+    /// The authority of the registry account
+    pub authority: Signer<'info>,
+
+    /// The registry page of the authority
+    #[account(mut,
+        constraint = registry_account.is_latest_version() @ ErrorCode::InvalidVersion,
+        constraint = registry_account.load()?.authority == authority.key())]
+    pub registry_account: AccountLoader<'info, RegistryAccount>,
+}
+
+/// Accounts for the instruction to detach a lookup table from the registry
+#[derive(Accounts)]
+pub struct DetachLookupTable<'info> {
+    /// The authority of the registry account
+    pub authority: Signer<'info>,
+
+    /// The registry account of the authority
+    #[account(mut,
+        constraint = registry_account.is_latest_version() @ ErrorCode::InvalidVersion,
+        constraint = registry_account.load()?.authority == authority.key())]
+    pub registry_account: AccountLoader<'info, RegistryAccount>,
+
+    /// The lookup table being detached
+    /// CHECK: the account is only used to find its entry in the registry
+    pub lookup_table: AccountInfo<'info>,
+}
+
+/// Accounts for the instruction to freeze a lookup table
+#[derive(Accounts)]
+pub struct FreezeLookupTable<'info> {
+    /// The authority of the registry account
+    pub authority: Signer<'info>,
+
+    /// The registry account of the authority
+    #[account(mut,
+        constraint = registry_account.is_latest_version() @ ErrorCode::InvalidVersion,
+        constraint = registry_account.load()?.authority == authority.key())]
+    pub registry_account: AccountLoader<'info, RegistryAccount>,
+
+    /// The lookup table being frozen
+    /// CHECK: the account will be validated by the lookup table program
+    #[account(mut)]
+    pub lookup_table: AccountInfo<'info>,
+
+    /// CHECK: the account will be validated by the lookup table program
+    #[account(address = solana_address_lookup_table_program::ID)]
+    pub address_lookup_table_program: AccountInfo<'info>,
+}
+
+/// Accounts for the instruction to set the label of a lookup table
+#[derive(Accounts)]
+pub struct SetEntryLabel<'info> {
+    /// The authority of the registry account
+    pub authority: Signer<'info>,
+
+    /// The registry account of the authority
+    #[account(mut,
+        constraint = registry_account.is_latest_version() @ ErrorCode::InvalidVersion,
+        constraint = registry_account.load()?.authority == authority.key())]
+    pub registry_account: AccountLoader<'info, RegistryAccount>,
+
+    /// The lookup table being labelled
+    /// CHECK: the account is only used to find its entry in the registry
+    pub lookup_table: AccountInfo<'info>,
+}
+
+/// Accounts for the instruction to propose a new registry authority
+#[derive(Accounts)]
+pub struct ProposeAuthority<'info> {
+    /// The current authority of the registry account
+    pub authority: Signer<'info>,
+
+    /// The registry account of the authority
+    #[account(mut,
+        constraint = registry_account.is_latest_version() @ ErrorCode::InvalidVersion,
+        constraint = registry_account.load()?.authority == authority.key(),
+        constraint = registry_account.load()?.page == 0,
+        constraint = !registry_account.load()?.is_public() @ ErrorCode::InvalidAuthority)]
+    pub registry_account: AccountLoader<'info, RegistryAccount>,
+}
+
+/// Accounts for the instruction to accept the authority of a registry
+#[derive(Accounts)]
+pub struct AcceptAuthority<'info> {
+    /// The proposed authority of the registry account
+    pub new_authority: Signer<'info>,
+
+    /// The first page of the registry account being transferred
+    #[account(mut,
+        constraint = registry_account.is_latest_version() @ ErrorCode::InvalidVersion,
+        constraint = registry_account.load()?.pending_authority == new_authority.key(),
+        constraint = registry_account.load()?.page == 0)]
+    pub registry_account: AccountLoader<'info, RegistryAccount>,
+}
+
+/// Accounts for the instruction to close a registry account
+#[derive(Accounts)]
+pub struct CloseRegistryAccount<'info> {
+    /// The authority of the registry account
+    pub authority: Signer<'info>,
+
+    /// The recipient of lamports
+    /// CHECK: the recipient is checked against the registry page if it has one
+    #[account(mut)]
+    pub recipient: AccountInfo<'info>,
+
+    /// The registry account being closed
+    #[account(mut,
+        close = recipient,
+        constraint = registry_account.is_latest_version() @ ErrorCode::InvalidVersion,
+        constraint = registry_account.load()?.authority == authority.key(),
+        constraint = registry_account.load()?.accepts_recipient(recipient.key) @ ErrorCode::InvalidRecipient,
+        constraint = registry_account.load()?.page == 0)]
+    pub registry_account: AccountLoader<'info, RegistryAccount>,
+
+    /// CHECK: the account will be validated by the lookup table program
+    #[account(address = solana_address_lookup_table_program::ID)]
+    pub address_lookup_table_program: AccountInfo<'info>,
+
+    /// The system program
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for the instruction to add a page to a registry
+#[derive(Accounts)]
+pub struct InitRegistryPage<'info> {
+    /// The authority of the registry account
+    pub authority: Signer<'info>,
+
+    /// The payer of the transaction
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// The first page of the registry, which tracks the number of pages
+    #[account(mut,
+        constraint = registry_account.is_latest_version() @ ErrorCode::InvalidVersion,
+        constraint = registry_account.load()?.authority == authority.key(),
+        constraint = registry_account.load()?.page == 0,
+        constraint = !registry_account.load()?.is_public() @ ErrorCode::InvalidPage)]
+    pub registry_account: AccountLoader<'info, RegistryAccount>,
+
+    /// The new page of the registry
+    #[account(init,
+        seeds = [
+            registry_account.load()?.seed_authority.as_ref(),
+            registry_account.load()?.name_seed(),
+            &[registry_account.load()?.page_count],
+        ],
+        bump,
+        payer = payer,
+        space = RegistryAccount::space(0))
+    ]
+    pub registry_page: AccountLoader<'info, RegistryAccount>,
+
+    /// The system program
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for the instruction to close the last page of a registry
+#[derive(Accounts)]
+pub struct CloseRegistryPage<'info> {
+    /// The authority of the registry account
+    pub authority: Signer<'info>,
+
+    /// The recipient of lamports
+    /// CHECK: the recipient is checked against the page if it has one
+    #[account(mut)]
+    pub recipient: AccountInfo<'info>,
+
+    /// The first page of the registry, which tracks the number of pages
+    #[account(mut,
+        constraint = registry_account.is_latest_version() @ ErrorCode::InvalidVersion,
+        constraint = registry_account.load()?.authority == authority.key(),
+        constraint = registry_account.load()?.page == 0)]
+    pub registry_account: AccountLoader<'info, RegistryAccount>,
+
+    /// The page being closed
+    #[account(mut,
+        close = recipient,
+        constraint = registry_page.is_latest_version() @ ErrorCode::InvalidVersion,
+        constraint = registry_page.load()?.page > 0,
+        constraint = registry_page.load()?.accepts_recipient(recipient.key) @ ErrorCode::InvalidRecipient,
+        seeds = [
+            registry_account.load()?.seed_authority.as_ref(),
+            registry_account.load()?.name_seed(),
+            &[registry_page.load()?.page],
+        ],
+        bump = registry_page.load()?.seed[0])]
+    pub registry_page: AccountLoader<'info, RegistryAccount>,
+
+    /// CHECK: the account will be validated by the lookup table program
+    #[account(address = solana_address_lookup_table_program::ID)]
+    pub address_lookup_table_program: AccountInfo<'info>,
+
+    /// The system program
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for the instruction to migrate a registry account to the latest version
+#[derive(Accounts)]
+pub struct MigrateRegistry<'info> {
+    /// The authority of the registry account
+    pub authority: Signer<'info>,
+
+    /// The payer of the rent for the larger registry account, which receives
+    /// the excess rent of a smaller registry account
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: the account is deserialized according to its version
+    #[account(mut, owner = crate::ID)]
+    pub registry_account: AccountInfo<'info>,
+
+    /// The system program
+    pub system_program: Program<'info, System>,
+}
+
+/// Accounts for the instruction to repair the counters of a registry page
+#[derive(Accounts)]
+pub struct RepairRegistry<'info> {
+    /// The registry page being repaired
+    #[account(mut,
+        constraint = registry_account.is_latest_version() @ ErrorCode::InvalidVersion)]
+    pub registry_account: AccountLoader<'info, RegistryAccount>,
+}
+
+/// Accounts for the instruction to verify a lookup table's membership
+#[derive(Accounts)]
+pub struct VerifyTable<'info> {
+    /// The registry page to check
+    #[account(
+        constraint = registry_account.is_latest_version() @ ErrorCode::InvalidVersion)]
+    pub registry_account: AccountLoader<'info, RegistryAccount>,
+
+    /// The lookup table being verified
+    /// CHECK: the account is only used to find its entry in the registry
+    pub lookup_table: AccountInfo<'info>,
+}
+
+/// Accounts for the instruction to compact a registry page
+#[derive(Accounts)]
+pub struct CompactRegistry<'info> {
+    /// The authority of the registry account
+    pub authority: Signer<'info>,
+
+    /// The recipient of the refunded lamports
+    /// CHECK: the recipient is checked against the registry page if it has one
+    #[account(mut)]
+    pub recipient: AccountInfo<'info>,
+
+    /// The registry page being compacted
+    #[account(mut,
+        constraint = registry_account.is_latest_version() @ ErrorCode::InvalidVersion,
+        constraint = registry_account.load()?.authority == authority.key(),
+        constraint = registry_account.load()?.accepts_recipient(recipient.key) @ ErrorCode::InvalidRecipient)]
+    pub registry_account: AccountLoader<'info, RegistryAccount>,
+}
+
 /// Errors used in the program
 #[error_code]
 pub enum ErrorCode {
@@ -417,8 +2225,8 @@ pub enum ErrorCode {
     #[msg("Invalid discriminator used")]
     InvalidDiscriminator = 10000,
 
-    /// The slot provided cannot be earlier than the last slot used
-    #[msg("Slot cannot be earlier than the last slot used")]
+    /// The slot provided has to be after the last slot used
+    #[msg("Slot has to be after the last slot used")]
     InvalidSlot,
 
     /// The lookup table provided is invalid
@@ -432,4 +2240,182 @@ pub enum ErrorCode {
     /// Thep rogram encountered some invalid state
     #[msg("The lookup registry is in an invalid state")]
     InvalidState,
+
+    /// The lookup table is frozen and can't be changed
+    #[msg("The lookup table is frozen")]
+    LookupTableFrozen,
+
+    /// The registry still has active lookup tables
+    #[msg("The registry account still has active lookup tables")]
+    RegistryNotEmpty,
+
+    /// A page of the registry is invalid or missing
+    #[msg("Invalid registry page")]
+    InvalidPage,
+
+    /// The registry has the maximum number of pages
+    #[msg("There are too many pages in the registry")]
+    TooManyPages,
+
+    /// The registry account's version is not supported by the instruction
+    #[msg("Unsupported registry version, the registry may have to be migrated")]
+    InvalidVersion,
+
+    /// The registry has lookup tables owned by its authority
+    #[msg("The registry has lookup tables owned by its authority")]
+    LegacyLookupTables,
+
+    /// The signer is not the authority of the registry
+    #[msg("The signer is not the authority of the registry")]
+    InvalidAuthority,
+
+    /// The recipient is not the one configured on the registry
+    #[msg("The recipient is not the registry's recipient")]
+    InvalidRecipient,
+
+    /// The delegate is not a delegate of the registry
+    #[msg("The delegate is not a delegate of the registry")]
+    InvalidDelegate,
+
+    /// All the delegate slots of a registry page are used
+    #[msg("The registry page has too many delegates")]
+    TooManyDelegates,
+}
+
+#[cfg(all(test, feature = "program"))]
+mod tests {
+    use solana_address_lookup_table_program::state::{AddressLookupTable, LookupTableMeta};
+    use std::borrow::Cow;
+
+    use super::*;
+
+    fn lookup_table_data(addresses: &[Pubkey], deactivation_slot: u64) -> Vec<u8> {
+        AddressLookupTable {
+            meta: LookupTableMeta {
+                deactivation_slot,
+                ..LookupTableMeta::default()
+            },
+            addresses: Cow::Borrowed(addresses),
+        }
+        .serialize_for_tests()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_dedup_addresses() {
+        let [a, b, c, d, e] = [(); 5].map(|_| Pubkey::new_unique());
+        let keys = [(); 3].map(|_| Pubkey::new_unique());
+        let owner = solana_address_lookup_table_program::ID;
+        let mut lamports = [0; 3];
+        let mut data = [
+            lookup_table_data(&[a], u64::MAX),
+            lookup_table_data(&[b], u64::MAX),
+            lookup_table_data(&[c], 100),
+        ];
+        let [table, active, deactivated] = {
+            let mut infos = keys
+                .iter()
+                .zip(lamports.iter_mut())
+                .zip(data.iter_mut())
+                .map(|((key, lamports), data)| {
+                    AccountInfo::new(key, false, false, lamports, data, &owner, false, 0)
+                });
+            [(); 3].map(|_| infos.next().unwrap())
+        };
+        let entries = [
+            RegistryEntry::new(2, keys[0], 0),
+            RegistryEntry::new(2, keys[1], 0),
+            RegistryEntry::new(2, keys[2], 0),
+        ];
+
+        // Addresses in the table, in active tables and repeated addresses are dropped
+        let addresses = dedup_addresses(
+            vec![e, a, b, c, d, e],
+            &entries,
+            &table,
+            &[active.clone(), deactivated.clone()],
+        )
+        .unwrap();
+        assert_eq!(addresses, vec![e, c, d]);
+
+        // Other tables have to be active in the registry page
+        let mut entries = entries;
+        entries[1].discriminator = discriminator::DEACTIVATED;
+        assert!(dedup_addresses(vec![d], &entries, &table, std::slice::from_ref(&active)).is_err());
+        assert!(dedup_addresses(vec![d], &entries[..1], &table, &[deactivated]).is_err());
+        assert_eq!(
+            dedup_addresses(vec![a, d], &entries, &table, &[]).unwrap(),
+            vec![d]
+        );
+    }
+
+    #[test]
+    fn test_excess_rent() {
+        let rent = Rent::default();
+        let lamports = rent.minimum_balance(RegistryAccount::space(6));
+
+        // Compacting two of six entries refunds the rent of their bytes
+        let refund = excess_rent(&rent, lamports, RegistryAccount::space(4));
+        assert_eq!(
+            refund,
+            lamports - rent.minimum_balance(RegistryAccount::space(4))
+        );
+        assert_eq!(
+            refund,
+            2 * REGISTRY_ENTRY_SIZE as u64
+                * rent.lamports_per_byte_year
+                * rent.exemption_threshold as u64
+        );
+
+        // Nothing is refunded to an account that isn't smaller
+        assert_eq!(excess_rent(&rent, lamports, RegistryAccount::space(6)), 0);
+        assert_eq!(excess_rent(&rent, lamports, RegistryAccount::space(8)), 0);
+    }
+
+    #[test]
+    fn test_check_cooldown() {
+        let key = Pubkey::new_unique();
+        let owner = solana_address_lookup_table_program::ID;
+        let mut lamports = 0;
+        let mut data = lookup_table_data(&[], u64::MAX);
+        let active_table = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            0,
+        );
+        let mut deactivated_lamports = 0;
+        let mut deactivated_data = lookup_table_data(&[], 1000);
+        let deactivated_table = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut deactivated_lamports,
+            &mut deactivated_data,
+            &owner,
+            false,
+            0,
+        );
+
+        // An active table can never be closed
+        let active = RegistryEntry::new(2, key, 10);
+        assert!(check_cooldown(&active, &active_table, u64::MAX).is_err());
+
+        // The slot recorded by the entry is used if it has one
+        let mut deactivated = RegistryEntry::new(discriminator::DEACTIVATED, key, 10);
+        deactivated.deactivated_slot = 2000;
+        let closeable = 2000 + DEACTIVATION_COOLDOWN;
+        assert!(check_cooldown(&deactivated, &deactivated_table, closeable).is_err());
+        assert!(check_cooldown(&deactivated, &deactivated_table, closeable + 1).is_ok());
+
+        // Legacy entries use the table's deactivation slot
+        let legacy = RegistryEntry::new(discriminator::DEACTIVATED, key, 0);
+        let closeable = 1000 + DEACTIVATION_COOLDOWN;
+        assert!(check_cooldown(&legacy, &deactivated_table, closeable).is_err());
+        assert!(check_cooldown(&legacy, &deactivated_table, closeable + 1).is_ok());
+    }
 }
@@ -1,4 +1,6 @@
-use anchor_lang::prelude::*;
+use std::cell::{Ref, RefMut};
+
+use anchor_lang::{prelude::*, Discriminator};
 
 // TODO: we can leave this as unlimited
 pub const REGISTRY_ENTRY_SIZE: usize = std::mem::size_of::<RegistryEntry>();
@@ -6,71 +8,890 @@ pub const REGISTRY_ENTRY_SIZE: usize = std::mem::size_of::<RegistryEntry>();
 ///
 /// Note that this size can be increased, however a practical limit of u8::MAX
 /// has been selected on a reasonable assumption that 255 entries are sufficient.
-/// Each lookup table can store up to 256 accounts, thus a registry can have 65k records.
-pub const MAX_REGISTRY_ENTRIES: usize =
-    (10240 - std::mem::size_of::<RegistryAccount>()) / REGISTRY_ENTRY_SIZE;
+/// Each lookup table can store up to 256 accounts, thus a registry page can have 23k records.
+/// A registry can have up to 255 pages when more entries are needed.
+pub const MAX_REGISTRY_ENTRIES: usize = (10240 - REGISTRY_HEADER_SIZE) / REGISTRY_ENTRY_SIZE;
 
-/// Current format allows up to 254 lookup accounts
-const _: () = assert!(MAX_REGISTRY_ENTRIES == 254);
+/// Current format allows up to 88 lookup accounts
+const _: () = assert!(MAX_REGISTRY_ENTRIES == 88);
 const _: () = assert!(MAX_REGISTRY_ENTRIES < u8::MAX as usize);
 
-/// A registry account that stores the lookup tables that an authority has created.
-#[account]
-#[repr(C)]
+/// Versions of the registry account's layout.
+///
+/// The version is always stored after the authority, so that the layout of any
+/// registry account can be determined. Registry accounts with an older version
+/// have to be migrated before they can be used.
+pub mod version {
+    /// The initial layout, whose lookup tables are owned by the registry authority
+    pub const V0: u8 = 0;
+    /// Zero-copy registries, whose entries follow a fixed-size header.
+    /// Registries are paged and own their lookup tables, and entries track
+    /// the state of their lookup table.
+    pub const V1: u8 = 1;
+    /// The version of newly created registry accounts
+    pub const LATEST: u8 = V1;
+
+    /// The offset of the version in the registry account's data
+    pub const OFFSET: usize = 8 + 32;
+}
+
+/// The header of a registry account that stores the lookup tables that an
+/// authority has created.
+///
+/// A registry is made up of pages, with the first page derived from the seed
+/// authority, and further pages from the seed authority and the page index.
+/// Each page is a registry account that creates and owns its own lookup tables.
+///
+/// The account is zero-copy: the header is followed by the page's entries,
+/// whose number is determined by the length of the account, see [RegistryPageLoader].
+#[account(zero_copy)]
 #[derive(Debug)]
 pub struct RegistryAccount {
     /// The authority that owns and signs for changes to the registry account
     pub authority: Pubkey,
-    /// The version of the registry account. The version denotes some change in
-    /// functionality.
-    /// - 0: initial version with no discriminators
+    /// The version of the registry account's layout, see [version]
     pub version: u8,
     /// The seed returned when deriving the registry account's address
     pub seed: [u8; 1],
-    /// The length of the populated entries in the registry
+    /// The number of populated entries in the page, which are the entries
+    /// that aren't [crate::discriminator::EMPTY]
     pub len: u8,
-    /// The allocated capacity of lookup entries. The capacity can be > len
+    /// The number of entries allocated in the page, including empty entries,
+    /// such that the capacity is always >= len
     pub capacity: u8,
+    /// The index of this page of the registry
+    pub page: u8,
+    /// The number of pages in the registry, only tracked by the first page
+    pub page_count: u8,
+    /// Whether the registry is a public registry of a program, see [PUBLIC_REGISTRY_SEED]
+    pub public: u8,
     /// Reserved bytes used as padding
-    pub reserved0: [u8; 4],
+    pub reserved0: [u8; 1],
     /// The slot when the last lookup table was created.
     /// Used to prevent a user creating multiple addresses in same slot
     pub last_created_slot: u64,
-    /// A growable list of registry entries
-    pub tables: Vec<RegistryEntry>,
+    /// The authority that the registry account's address is derived from.
+    /// This differs from the authority once the registry has been transferred.
+    /// The program of a public registry.
+    pub seed_authority: Pubkey,
+    /// The authority that has been proposed to take over the registry, if any
+    pub pending_authority: Pubkey,
+    /// The recipient of the rent of lookup tables that are closed by anyone
+    /// once their cooldown has elapsed, see [crate::lookup_table_registry::close_lookup_table].
+    /// Closing tables permissionlessly is disabled if it is the default pubkey.
+    ///
+    /// If set, the authority also has to send the lamports of closed tables
+    /// and accounts to it.
+    pub recipient: Pubkey,
+    /// The keys that can extend lookup tables of the page with a discriminator,
+    /// see [RegistryAccount::can_append]
+    pub delegates: [RegistryDelegate; MAX_REGISTRY_DELEGATES],
+    /// The name of the registry, which is part of the seeds of its pages.
+    /// All zeros for the registry that is derived from the authority alone.
+    pub name: [u8; REGISTRY_NAME_LEN],
+    /// Reserved bytes for future fields of the header
+    pub reserved1: [u8; 8],
 }
 
+/// The length of a registry's name.
+///
+/// Names have a fixed length, so that the seeds of a named registry can't
+/// collide with the seeds of another registry's pages.
+pub const REGISTRY_NAME_LEN: usize = 32;
+
+/// The seed of a registry's name, which is empty for the registry that is
+/// derived from the authority alone.
+pub fn registry_name_seed(name: &[u8; REGISTRY_NAME_LEN]) -> &[u8] {
+    if name == &[0; REGISTRY_NAME_LEN] {
+        &[]
+    } else {
+        name
+    }
+}
+
+/// The maximum number of delegates of a registry page
+pub const MAX_REGISTRY_DELEGATES: usize = 4;
+
+/// A key that can extend the lookup tables of a registry page that have a
+/// discriminator, but can't create or remove lookup tables.
+///
+/// A delegate can only extend lookup tables owned by the registry account,
+/// as the authority has to sign for the tables that it owns.
+#[zero_copy]
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct RegistryDelegate {
+    /// The delegate, or the default pubkey if the slot is unused
+    pub delegate: Pubkey,
+    /// The discriminator of the lookup tables that the delegate can extend
+    pub discriminator: u64,
+}
+
+/// The seed of public registries, which are derived from this seed and a
+/// program ID.
+///
+/// Anyone can extend the lookup tables of a public registry, paying for them.
+/// Its authority is an optional curator, which can create, freeze and remove
+/// lookup tables, and is the default pubkey if there is no curator, in which
+/// case anyone can also create lookup tables, paying for them.
+/// A public registry only has a single page.
+pub const PUBLIC_REGISTRY_SEED: &[u8] = b"public";
+
+/// The maximum number of lookup tables created by a single instruction.
+///
+/// Each table adds its address and slot to the transaction, which limits a
+/// transaction to about 20 tables, and the addresses of the tables are
+/// returned in the return data, which is limited to 1024 bytes.
+pub const MAX_CREATE_LOOKUP_TABLES: usize = 16;
+
+const _: () = assert!(
+    4 + MAX_CREATE_LOOKUP_TABLES * 32 <= anchor_lang::solana_program::program::MAX_RETURN_DATA
+);
+
+/// The size of a registry account without any entries, including its discriminator
+pub const REGISTRY_HEADER_SIZE: usize = 8 + std::mem::size_of::<RegistryAccount>();
+
+/// The number of slots that a lookup table has to be deactivated for before
+/// it can be closed, which is the number of slots in the slot hashes sysvar.
+pub const DEACTIVATION_COOLDOWN: u64 = 512;
+
+/// The length of an entry's label
+pub const ENTRY_LABEL_LEN: usize = 32;
+/// The length of an entry's metadata
+pub const ENTRY_METADATA_LEN: usize = 16;
+
 /// An entry that tracks a lookup table and its state.
-#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone)]
+#[zero_copy]
+#[derive(Debug)]
 pub struct RegistryEntry {
     /// An identifier to track the state (and in future purpose) of an entry
     pub discriminator: u64,
     /// The lookup table address
     pub table: Pubkey,
+    /// Whether the lookup table has been frozen, and can thus never change
+    pub frozen: u8,
+    /// Whether the lookup table's authority is the registry authority instead
+    /// of the registry account, as is the case for tables created before [version::V1]
+    pub legacy_authority: u8,
+    /// Reserved bytes used as padding
+    pub reserved0: [u8; 6],
+    /// A human-readable label of the lookup table, padded with zeros
+    pub label: [u8; ENTRY_LABEL_LEN],
+    /// Metadata that the authority can attach to the lookup table
+    pub metadata: [u8; ENTRY_METADATA_LEN],
+    /// The slot when the lookup table was created, 0 if it was created
+    /// before [version::V1]
+    pub created_slot: u64,
+    /// The slot when the lookup table was deactivated, 0 if it is active or
+    /// was deactivated before [version::V1]
+    pub deactivated_slot: u64,
+}
+
+impl RegistryEntry {
+    /// An entry for a lookup table owned by the registry account
+    pub fn new(discriminator: u64, table: Pubkey, created_slot: u64) -> Self {
+        Self {
+            discriminator,
+            table,
+            frozen: 0,
+            legacy_authority: 0,
+            reserved0: [0; 6],
+            label: [0; ENTRY_LABEL_LEN],
+            metadata: [0; ENTRY_METADATA_LEN],
+            created_slot,
+            deactivated_slot: 0,
+        }
+    }
+
+    /// Whether the lookup table has been frozen
+    pub fn is_frozen(&self) -> bool {
+        self.frozen != 0
+    }
+
+    /// Whether the lookup table's authority is the registry authority
+    pub fn is_legacy_authority(&self) -> bool {
+        self.legacy_authority != 0
+    }
+
+    /// The slot after which a deactivated lookup table can be closed at the
+    /// earliest, which is once its deactivation slot leaves the slot hashes.
+    ///
+    /// Returns `None` if the table is not deactivated, or if it was deactivated
+    /// before the slot was recorded, in which case the lookup table's
+    /// `deactivation_slot` has to be used instead.
+    pub fn closeable_after_slot(&self) -> Option<u64> {
+        if self.discriminator != crate::discriminator::DEACTIVATED || self.deactivated_slot == 0 {
+            return None;
+        }
+        Some(self.deactivated_slot + DEACTIVATION_COOLDOWN)
+    }
+
+    /// The label as a string, up to the first zero byte
+    pub fn label_string(&self) -> String {
+        let len = self
+            .label
+            .iter()
+            .position(|byte| *byte == 0)
+            .unwrap_or(ENTRY_LABEL_LEN);
+        String::from_utf8_lossy(&self.label[..len]).into_owned()
+    }
+}
+
+/// The layout of a [version::V0] registry account
+#[derive(AnchorDeserialize, Debug)]
+pub struct RegistryAccountV0 {
+    pub authority: Pubkey,
+    pub version: u8,
+    pub seed: [u8; 1],
+    pub len: u8,
+    pub capacity: u8,
+    pub reserved0: [u8; 4],
+    pub last_created_slot: u64,
+    pub tables: Vec<RegistryEntryV0>,
+}
+
+/// The layout of a [version::V0] registry entry
+#[derive(AnchorDeserialize, Debug)]
+pub struct RegistryEntryV0 {
+    pub discriminator: u64,
+    pub table: Pubkey,
+}
+
+impl From<RegistryAccountV0> for RegistryPage {
+    fn from(value: RegistryAccountV0) -> Self {
+        Self {
+            header: RegistryAccount {
+                authority: value.authority,
+                version: value.version,
+                seed: value.seed,
+                len: value.len,
+                capacity: value.capacity,
+                page: 0,
+                page_count: 1,
+                public: 0,
+                reserved0: [0; 1],
+                last_created_slot: value.last_created_slot,
+                seed_authority: value.authority,
+                pending_authority: Pubkey::default(),
+                recipient: Pubkey::default(),
+                delegates: Default::default(),
+                name: [0; REGISTRY_NAME_LEN],
+                reserved1: [0; 8],
+            },
+            tables: value
+                .tables
+                .into_iter()
+                .map(|entry| RegistryEntry {
+                    legacy_authority: 1,
+                    ..RegistryEntry::new(entry.discriminator, entry.table, 0)
+                })
+                .collect(),
+        }
+    }
 }
 
 impl RegistryAccount {
+    /// The size of a registry account with the number of entries
+    pub const fn space(entries: usize) -> usize {
+        REGISTRY_HEADER_SIZE + entries * REGISTRY_ENTRY_SIZE
+    }
+
+    /// Whether the registry is a public registry of a program
+    pub fn is_public(&self) -> bool {
+        self.public != 0
+    }
+
+    /// Whether the signer can create lookup tables in the registry, which only
+    /// the authority can do, being the curator of a public registry.
+    /// Anyone can create lookup tables in a public registry without a curator.
+    pub fn can_create(&self, signer: &Pubkey) -> bool {
+        if self.authority == Pubkey::default() {
+            return self.is_public();
+        }
+        &self.authority == signer
+    }
+
+    /// Whether the signer can extend lookup tables in the registry,
+    /// which anyone can do in a public registry.
+    pub fn can_extend(&self, signer: &Pubkey) -> bool {
+        self.is_public() || &self.authority == signer
+    }
+
+    /// Recompute the counters of the header from the entries of the page.
+    pub fn update_counters(&mut self, tables: &[RegistryEntry]) {
+        self.len = (tables.len() - tables.empty_entries()) as u8;
+        self.capacity = tables.len() as u8;
+    }
+
+    /// Whether the counters of the header match the entries of the page.
+    pub fn has_valid_counters(&self, tables: &[RegistryEntry]) -> bool {
+        self.len as usize == tables.len() - tables.empty_entries()
+            && self.capacity as usize == tables.len()
+    }
+
+    /// Whether the signer can extend the lookup tables with a discriminator,
+    /// which the authority and its delegates for the discriminator can do.
+    pub fn can_append(&self, signer: &Pubkey, discriminator: u64) -> bool {
+        self.can_extend(signer) || self.is_delegate(signer, discriminator)
+    }
+
+    /// Whether the key is a delegate for the discriminator
+    pub fn is_delegate(&self, delegate: &Pubkey, discriminator: u64) -> bool {
+        delegate != &Pubkey::default()
+            && self
+                .delegates
+                .iter()
+                .any(|entry| &entry.delegate == delegate && entry.discriminator == discriminator)
+    }
+
+    /// Whether the registry account has an active lookup table, which is one
+    /// that is neither empty nor deactivated. Frozen lookup tables are active.
+    ///
+    /// This is useful for programs that read a registry account directly to
+    /// check that a lookup table comes from the registry. The account has to
+    /// be a registry account on the latest version, see [RegistryPageLoader].
+    /// Registries with multiple pages have to be checked page by page.
+    ///
+    /// Anyone can create a registry account, so the account is only trusted
+    /// if its authority is the expected authority, and errors otherwise.
+    pub fn contains_active<'info>(
+        account: &AccountInfo<'info>,
+        authority: &Pubkey,
+        table: &Pubkey,
+    ) -> Result<bool> {
+        let registry = AccountLoader::<RegistryAccount>::try_from(account)?;
+        if !registry.is_latest_version() {
+            return err!(crate::ErrorCode::InvalidVersion);
+        }
+        let (header, tables) = registry.load_page()?;
+        if &header.authority != authority {
+            msg!("Expected a registry of {}", authority);
+            return err!(crate::ErrorCode::InvalidAuthority);
+        }
+        Ok(tables.contains_active(table))
+    }
+
+    /// Whether lamports can be sent to the recipient, which has to be the
+    /// recipient configured on the registry page if it has one.
+    pub fn accepts_recipient(&self, recipient: &Pubkey) -> bool {
+        self.recipient == Pubkey::default() || &self.recipient == recipient
+    }
+
+    /// The seed of the registry's name, see [registry_name_seed]
+    pub fn name_seed(&self) -> &[u8] {
+        registry_name_seed(&self.name)
+    }
+
+    /// The seeds to sign for the lookup tables owned by the registry page.
+    ///
+    /// The first page is derived without a page index, and registries without
+    /// a name are derived without a name.
+    pub fn signer_seeds(&self) -> [&[u8]; 4] {
+        if self.is_public() {
+            return [
+                PUBLIC_REGISTRY_SEED,
+                self.seed_authority.as_ref(),
+                &[],
+                &self.seed,
+            ];
+        }
+        let page: &[u8] = if self.page == 0 {
+            &[]
+        } else {
+            std::slice::from_ref(&self.page)
+        };
+        [
+            self.seed_authority.as_ref(),
+            self.name_seed(),
+            page,
+            &self.seed,
+        ]
+    }
+}
+
+/// The status of a lookup table in a registry
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TableStatus {
+    /// The lookup table is not in the registry
+    Missing,
+    /// The lookup table is active and can be extended
+    Active,
+    /// The lookup table is active and can never change
+    Frozen,
+    /// The lookup table is deactivated, and will be closed
+    Deactivated,
+}
+
+/// The membership of a lookup table in a registry, which is returned by
+/// [crate::lookup_table_registry::verify_table].
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TableVerification {
+    /// The authority of the registry
+    pub authority: Pubkey,
+    /// The discriminator of the lookup table's entry, or
+    /// [crate::discriminator::EMPTY] if it's missing
+    pub discriminator: u64,
+    /// The status of the lookup table
+    pub status: TableStatus,
+}
+
+impl TableVerification {
+    /// Whether the lookup table is active in the registry
+    pub fn is_active(&self) -> bool {
+        matches!(self.status, TableStatus::Active | TableStatus::Frozen)
+    }
+}
+
+/// Lookups on the entries of a registry page
+pub trait RegistryEntries {
+    /// The number of entries with an [crate::discriminator::EMPTY] discriminator
+    fn empty_entries(&self) -> usize;
+
     /// Find an entry in the registry by its address
-    pub fn find_entry(&self, address: &Pubkey) -> Result<&RegistryEntry> {
-        self.tables
-            .iter()
+    fn find_entry(&self, address: &Pubkey) -> Result<&RegistryEntry>;
+
+    /// Whether the registry has an active lookup table, see [RegistryAccount::contains_active]
+    fn contains_active(&self, address: &Pubkey) -> bool;
+
+    /// Whether the registry has lookup tables owned by its authority instead
+    /// of the registry account, see [RegistryEntry::is_legacy_authority]
+    fn has_legacy_tables(&self) -> bool;
+
+    /// Find an entry in the registry by its address for mutation
+    fn find_entry_mut(&mut self, address: &Pubkey) -> Result<&mut RegistryEntry>;
+
+    /// Find an empty entry in the registry. An empty entry is one with a discriminator = [crate::discriminator::EMPTY]
+    fn find_empty_entry(&mut self) -> Result<&mut RegistryEntry>;
+
+    /// Move the entries that aren't empty to the front of the registry in their
+    /// existing order if `reorder` is set, and return the number of entries up
+    /// to the last one that isn't empty, which are the entries to keep.
+    fn compact(&mut self, reorder: bool) -> usize;
+}
+
+impl RegistryEntries for [RegistryEntry] {
+    fn empty_entries(&self) -> usize {
+        self.iter()
+            .filter(|entry| entry.discriminator == crate::discriminator::EMPTY)
+            .count()
+    }
+
+    fn find_entry(&self, address: &Pubkey) -> Result<&RegistryEntry> {
+        self.iter()
             .find(|entry| &entry.table == address)
             .ok_or(crate::ErrorCode::InvalidLookupTable.into())
     }
 
-    /// Find an entry in the registry by its address for mutation
-    pub fn find_entry_mut(&mut self, address: &Pubkey) -> Result<&mut RegistryEntry> {
-        self.tables
-            .iter_mut()
+    fn contains_active(&self, address: &Pubkey) -> bool {
+        self.find_entry(address)
+            .map(|entry| entry.discriminator > crate::discriminator::DEACTIVATED)
+            .unwrap_or(false)
+    }
+
+    fn has_legacy_tables(&self) -> bool {
+        self.iter().any(|entry| {
+            entry.is_legacy_authority() && entry.discriminator != crate::discriminator::EMPTY
+        })
+    }
+
+    fn find_entry_mut(&mut self, address: &Pubkey) -> Result<&mut RegistryEntry> {
+        self.iter_mut()
             .find(|entry| &entry.table == address)
             .ok_or(crate::ErrorCode::InvalidLookupTable.into())
     }
 
-    /// Find an empty entry in the registry. An empty entry is one with a discriminator = [crate::discriminator::EMPTY]
-    pub fn find_empty_entry(&mut self) -> Result<&mut RegistryEntry> {
-        self.tables
-            .iter_mut()
+    fn find_empty_entry(&mut self) -> Result<&mut RegistryEntry> {
+        self.iter_mut()
             .find(|entry| entry.discriminator == crate::discriminator::EMPTY)
             .ok_or(crate::ErrorCode::InvalidLookupTable.into())
     }
+
+    fn compact(&mut self, reorder: bool) -> usize {
+        let mut entries = 0;
+        if reorder {
+            for index in 0..self.len() {
+                let entry = self[index];
+                if entry.discriminator != crate::discriminator::EMPTY {
+                    self[entries] = entry;
+                    entries += 1;
+                }
+            }
+        } else {
+            entries = self.len();
+            while entries > 0 && self[entries - 1].discriminator == crate::discriminator::EMPTY {
+                entries -= 1;
+            }
+        }
+        entries
+    }
+}
+
+/// Access to the entries of a registry account, which follow its header
+pub trait RegistryPageLoader {
+    /// Whether the account is large enough for the header, and has the latest version
+    fn is_latest_version(&self) -> bool;
+
+    /// Borrow the header and the entries of the registry page
+    fn load_page(&self) -> Result<(Ref<'_, RegistryAccount>, Ref<'_, [RegistryEntry]>)>;
+
+    /// Borrow the header and the entries of the registry page for mutation
+    fn load_page_mut(&self) -> Result<(RefMut<'_, RegistryAccount>, RefMut<'_, [RegistryEntry]>)>;
+}
+
+impl<'info> RegistryPageLoader for AccountLoader<'info, RegistryAccount> {
+    fn is_latest_version(&self) -> bool {
+        let Ok(data) = self.as_ref().try_borrow_data() else {
+            return false;
+        };
+        data.len() >= REGISTRY_HEADER_SIZE && data[version::OFFSET] == version::LATEST
+    }
+
+    fn load_page(&self) -> Result<(Ref<'_, RegistryAccount>, Ref<'_, [RegistryEntry]>)> {
+        // Checks the discriminator
+        drop(self.load()?);
+        let data = self.as_ref().try_borrow_data()?;
+        let entries = entry_count(data.len())?;
+        Ok(Ref::map_split(data, |data| {
+            let (header, tables) = data[8..].split_at(REGISTRY_HEADER_SIZE - 8);
+            (
+                bytemuck::from_bytes(header),
+                bytemuck::cast_slice(&tables[..entries * REGISTRY_ENTRY_SIZE]),
+            )
+        }))
+    }
+
+    fn load_page_mut(&self) -> Result<(RefMut<'_, RegistryAccount>, RefMut<'_, [RegistryEntry]>)> {
+        // Checks the discriminator and that the account is writable
+        drop(self.load_mut()?);
+        let data = self.as_ref().try_borrow_mut_data()?;
+        let entries = entry_count(data.len())?;
+        Ok(RefMut::map_split(data, |data| {
+            let (header, tables) = data[8..].split_at_mut(REGISTRY_HEADER_SIZE - 8);
+            (
+                bytemuck::from_bytes_mut(header),
+                bytemuck::cast_slice_mut(&mut tables[..entries * REGISTRY_ENTRY_SIZE]),
+            )
+        }))
+    }
+}
+
+/// The number of entries in a registry account of the length
+fn entry_count(data_len: usize) -> Result<usize> {
+    if data_len < REGISTRY_HEADER_SIZE {
+        return err!(anchor_lang::error::ErrorCode::AccountDidNotDeserialize);
+    }
+    Ok((data_len - REGISTRY_HEADER_SIZE) / REGISTRY_ENTRY_SIZE)
+}
+
+/// An owned copy of a registry page, with its header and entries.
+///
+/// Used by clients, and to migrate registry accounts of older versions.
+#[derive(Debug, Clone)]
+pub struct RegistryPage {
+    /// The header of the registry account
+    pub header: RegistryAccount,
+    /// The entries of the registry page
+    pub tables: Vec<RegistryEntry>,
+}
+
+impl std::ops::Deref for RegistryPage {
+    type Target = RegistryAccount;
+
+    fn deref(&self) -> &Self::Target {
+        &self.header
+    }
+}
+
+impl RegistryPage {
+    /// Deserialize a registry account of any known version into the latest layout.
+    ///
+    /// The version of the returned account is the stored version, so that
+    /// callers can tell whether the account has to be migrated.
+    pub fn try_deserialize_versioned(buf: &mut &[u8]) -> Result<Self> {
+        let Some(stored_version) = buf.get(version::OFFSET) else {
+            return err!(anchor_lang::error::ErrorCode::AccountDidNotDeserialize);
+        };
+        match *stored_version {
+            version::V0 => Ok(Self::try_deserialize_legacy::<RegistryAccountV0>(buf)?.into()),
+            version::LATEST => Self::try_deserialize(buf),
+            _ => err!(crate::ErrorCode::InvalidVersion),
+        }
+    }
+
+    /// Deserialize a zero-copy registry account, which might not be aligned
+    fn try_deserialize(buf: &[u8]) -> Result<Self> {
+        Self::check_discriminator(buf)?;
+        let entries = entry_count(buf.len())?;
+        let (header, tables) = buf.split_at(REGISTRY_HEADER_SIZE);
+        Ok(Self {
+            header: bytemuck::pod_read_unaligned(&header[8..]),
+            tables: tables
+                .chunks_exact(REGISTRY_ENTRY_SIZE)
+                .take(entries)
+                .map(bytemuck::pod_read_unaligned)
+                .collect(),
+        })
+    }
+
+    /// Deserialize the layout of an older version of the registry account
+    fn try_deserialize_legacy<T: AnchorDeserialize>(buf: &[u8]) -> Result<T> {
+        Self::check_discriminator(buf)?;
+        let mut data = &buf[8..];
+        T::deserialize(&mut data)
+            .map_err(|_| anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into())
+    }
+
+    fn check_discriminator(buf: &[u8]) -> Result<()> {
+        if buf.len() < 8 || buf[..8] != RegistryAccount::DISCRIMINATOR {
+            return err!(anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch);
+        }
+        Ok(())
+    }
+
+    /// Serialize the page into the data of a registry account, which has to be
+    /// exactly as large as the page.
+    pub fn serialize_into(&self, data: &mut [u8]) -> Result<()> {
+        if data.len() != RegistryAccount::space(self.tables.len()) {
+            return err!(anchor_lang::error::ErrorCode::AccountDidNotSerialize);
+        }
+        let (header, tables) = data.split_at_mut(REGISTRY_HEADER_SIZE);
+        header[..8].copy_from_slice(&RegistryAccount::DISCRIMINATOR);
+        header[8..].copy_from_slice(bytemuck::bytes_of(&self.header));
+        tables.copy_from_slice(bytemuck::cast_slice(&self.tables));
+        Ok(())
+    }
+
+    /// Convert a page of an older version to the latest version.
+    ///
+    /// [version::V0] registries didn't keep the counters in sync with the
+    /// entries, and could have more than [MAX_REGISTRY_ENTRIES] entries.
+    /// The empty entries of such a page are dropped, and a page that still has
+    /// too many entries keeps them all. It can't grow any further, but reuses
+    /// the entries of lookup tables that are removed.
+    pub fn migrate(&mut self) {
+        self.header.version = version::LATEST;
+        if self.tables.len() > MAX_REGISTRY_ENTRIES {
+            self.tables
+                .retain(|entry| entry.discriminator != crate::discriminator::EMPTY);
+        }
+        self.header.update_counters(&self.tables);
+    }
+
+    /// Whether all the entries of the page are populated, such that a new
+    /// page is needed to create more lookup tables.
+    pub fn is_full(&self) -> bool {
+        self.available_entries() == 0
+    }
+
+    /// The number of lookup tables that can still be created in the page,
+    /// either in empty entries or by growing the page.
+    pub fn available_entries(&self) -> usize {
+        MAX_REGISTRY_ENTRIES.saturating_sub(self.tables.len()) + self.empty_entries()
+    }
+
+    /// The number of entries with an [crate::discriminator::EMPTY] discriminator
+    pub fn empty_entries(&self) -> usize {
+        self.tables.empty_entries()
+    }
+
+    /// Find an entry in the registry by its address
+    pub fn find_entry(&self, address: &Pubkey) -> Result<&RegistryEntry> {
+        self.tables.find_entry(address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serialize a migrated page into a zero-copy account and read it back
+    fn round_trip(page: &RegistryPage) -> RegistryPage {
+        let mut data = vec![0; RegistryAccount::space(page.tables.len())];
+        page.serialize_into(&mut data).unwrap();
+        let page = RegistryPage::try_deserialize_versioned(&mut &data[..]).unwrap();
+        assert_eq!(page.version, version::LATEST);
+        page
+    }
+
+    /// The data of a [version::V0] registry account with the entries
+    fn v0_data(authority: &Pubkey, entries: &[(u64, Pubkey)]) -> Vec<u8> {
+        let mut data = RegistryAccount::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(authority.as_ref());
+        data.extend_from_slice(&[version::V0, 254, 0, 0, 0, 0, 0, 0]);
+        data.extend_from_slice(&7u64.to_le_bytes());
+        data.extend_from_slice(&(entries.len() as u32).to_le_bytes());
+        for (discriminator, table) in entries {
+            data.extend_from_slice(&discriminator.to_le_bytes());
+            data.extend_from_slice(table.as_ref());
+        }
+        data
+    }
+
+    #[test]
+    fn test_migrate_v0_page() {
+        let authority = Pubkey::new_unique();
+        let table = Pubkey::new_unique();
+        let data = v0_data(&authority, &[(2, table), (0, Pubkey::default())]);
+
+        let mut page = RegistryPage::try_deserialize_versioned(&mut &data[..]).unwrap();
+        assert_eq!(page.version, version::V0);
+        page.migrate();
+
+        let page = round_trip(&page);
+        assert_eq!((page.len, page.capacity), (1, 2));
+        assert_eq!((page.page, page.page_count), (0, 1));
+        assert_eq!(page.seed, [254]);
+        assert_eq!(page.last_created_slot, 7);
+        assert_eq!(page.seed_authority, authority);
+        assert_eq!(page.signer_seeds()[0], authority.as_ref());
+        let entry = page.find_entry(&table).unwrap();
+        assert_eq!(entry.discriminator, 2);
+        assert!(entry.is_legacy_authority());
+        assert!(!entry.is_frozen());
+    }
+
+    #[test]
+    fn test_migrate_oversized_v0_page() {
+        let authority = Pubkey::new_unique();
+        let entry = |index| match index % 5 {
+            0 => (0, Pubkey::default()),
+            _ => (2, Pubkey::new_unique()),
+        };
+
+        // Empty entries are dropped to fit the page
+        let entries = (0..100).map(entry).collect::<Vec<_>>();
+        let mut page =
+            RegistryPage::try_deserialize_versioned(&mut &v0_data(&authority, &entries)[..])
+                .unwrap();
+        page.migrate();
+        let page = round_trip(&page);
+        assert_eq!((page.len, page.capacity), (80, 80));
+        assert_eq!(page.available_entries(), MAX_REGISTRY_ENTRIES - 80);
+        let tables = entries
+            .iter()
+            .filter(|(discriminator, _)| *discriminator != 0)
+            .map(|(_, table)| *table);
+        assert!(tables.eq(page.tables.iter().map(|entry| entry.table)));
+
+        // A page with more entries keeps them all, but is full
+        let entries = (0..254).map(entry).collect::<Vec<_>>();
+        let mut page =
+            RegistryPage::try_deserialize_versioned(&mut &v0_data(&authority, &entries)[..])
+                .unwrap();
+        page.migrate();
+        let page = round_trip(&page);
+        assert_eq!((page.len, page.capacity), (203, 203));
+        assert!(page.is_full());
+    }
+
+    fn entries(discriminators: &[u64]) -> Vec<RegistryEntry> {
+        discriminators
+            .iter()
+            .map(|discriminator| match discriminator {
+                0 => RegistryEntry::new(0, Pubkey::default(), 0),
+                _ => RegistryEntry::new(*discriminator, Pubkey::new_unique(), 0),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_counters() {
+        let tables = entries(&[2, 0, 1, 3, 0]);
+        let mut header = <RegistryAccount as bytemuck::Zeroable>::zeroed();
+        assert!(!header.has_valid_counters(&tables));
+
+        header.update_counters(&tables);
+        assert_eq!((header.len, header.capacity), (3, 5));
+        assert!(header.has_valid_counters(&tables));
+        assert!(!header.has_valid_counters(&tables[..4]));
+
+        header.len = 5;
+        assert!(!header.has_valid_counters(&tables));
+    }
+
+    #[test]
+    fn test_compact_trailing_entries() {
+        let mut tables = entries(&[0, 2, 0, 1, 0, 0]);
+        let before = tables.iter().map(|entry| entry.table).collect::<Vec<_>>();
+
+        let kept = tables.compact(false);
+        assert_eq!(kept, 4);
+        assert!(before.iter().eq(tables.iter().map(|entry| &entry.table)));
+
+        let mut header = <RegistryAccount as bytemuck::Zeroable>::zeroed();
+        header.update_counters(&tables[..kept]);
+        assert_eq!((header.len, header.capacity), (2, 4));
+        assert!(header.has_valid_counters(&tables[..kept]));
+    }
+
+    #[test]
+    fn test_compact_reordered_entries() {
+        let mut tables = entries(&[0, 2, 0, 1, 0, 3]);
+        let active = tables
+            .iter()
+            .filter(|entry| entry.discriminator != crate::discriminator::EMPTY)
+            .map(|entry| (entry.discriminator, entry.table))
+            .collect::<Vec<_>>();
+
+        let kept = tables.compact(true);
+        assert_eq!(kept, 3);
+        let compacted = tables[..kept]
+            .iter()
+            .map(|entry| (entry.discriminator, entry.table))
+            .collect::<Vec<_>>();
+        assert_eq!(compacted, active);
+
+        let mut header = <RegistryAccount as bytemuck::Zeroable>::zeroed();
+        header.update_counters(&tables[..kept]);
+        assert_eq!((header.len, header.capacity), (3, 3));
+
+        // Compacting empty and already compact pages keeps them
+        assert_eq!(entries(&[0, 0]).compact(true), 0);
+        assert_eq!(entries(&[0, 0]).compact(false), 0);
+        assert_eq!(tables[..kept].compact(false), 3);
+    }
+
+    #[test]
+    fn test_closeable_after_slot() {
+        let table = Pubkey::new_unique();
+        let mut entry = RegistryEntry::new(2, table, 100);
+        entry.deactivated_slot = 200;
+        assert_eq!(entry.closeable_after_slot(), None);
+
+        entry.discriminator = crate::discriminator::DEACTIVATED;
+        assert_eq!(
+            entry.closeable_after_slot(),
+            Some(200 + DEACTIVATION_COOLDOWN)
+        );
+
+        // Tables deactivated before the slot was recorded
+        entry.deactivated_slot = 0;
+        assert_eq!(entry.closeable_after_slot(), None);
+    }
+
+    #[test]
+    fn test_can_append() {
+        let authority = Pubkey::new_unique();
+        let delegate = Pubkey::new_unique();
+        let anyone = Pubkey::new_unique();
+        let mut header = <RegistryAccount as bytemuck::Zeroable>::zeroed();
+        header.authority = authority;
+        header.delegates[1] = RegistryDelegate {
+            delegate,
+            discriminator: 2,
+        };
+
+        assert!(header.can_append(&authority, 3));
+        assert!(header.can_append(&delegate, 2));
+        assert!(!header.can_append(&delegate, 3));
+        // Other signers can only append in a public registry
+        assert!(!header.can_append(&anyone, 2));
+
+        // Unused delegate slots don't make the default pubkey a delegate
+        assert!(!header.is_delegate(&Pubkey::default(), 0));
+        assert!(!header.can_append(&Pubkey::default(), 0));
+
+        // Anyone can append in a public registry
+        header.public = 1;
+        assert!(header.can_append(&anyone, 3));
+    }
 }
@@ -1,7 +1,7 @@
 use std::net::SocketAddr;
 use std::sync::Arc;
 
-use axum::extract::Path;
+use axum::extract::{Path, Query};
 use axum::routing::{get, post};
 use axum::{response::IntoResponse, Extension, Json, Router};
 use lookup_table_registry_client::reader::LookupRegistryReader;
@@ -44,26 +44,37 @@ async fn main() {
 async fn get_authority_addresses(
     Extension(context): Extension<ApiContext>,
     Path(authority): Path<String>,
+    Query(query): Query<RegistryQuery>,
 ) -> impl IntoResponse {
     // Check that authority is a valid pubkey
     let Ok(authority) = authority.parse::<Pubkey>() else {
-        return Json(GetAuthorityAddressesResponse { authority: Default::default(), addresses: vec![] })
+        return Json(GetAuthorityAddressesResponse {
+            authority: Default::default(),
+            addresses: vec![],
+            tables: vec![],
+        });
     };
-    let addresses = context
+    let tables = context
         .registry_client
-        .get_registry(&authority)
+        .get_registry(&authority, query.name.as_deref())
         .await
         .map(|registry| {
             registry
                 .tables
                 .iter()
-                .map(|table| table.lookup_address)
+                .map(|table| AuthorityTable {
+                    address: table.lookup_address,
+                    discriminator: table.discriminator,
+                    label: table.label.clone(),
+                    metadata: table.metadata.to_vec(),
+                })
                 .collect::<Vec<_>>()
         })
         .unwrap_or_default();
     Json(GetAuthorityAddressesResponse {
         authority,
-        addresses,
+        addresses: tables.iter().map(|table| table.address).collect(),
+        tables,
     })
 }
 
@@ -74,16 +85,18 @@ async fn get_lookup_addresses(
     // Refresh lookup addresses by authority
     context
         .registry_client
-        .update_registries(&input.authorities)
+        .update_registries(&input.authorities, input.name.as_deref())
         .await;
     let instructions = input
         .instructions
         .iter()
         .map(|ix| ix.into())
         .collect::<Vec<_>>();
-    let result = context
-        .registry_client
-        .find_addresses(&instructions, &input.authorities);
+    let result = context.registry_client.find_addresses(
+        &instructions,
+        &input.authorities,
+        input.name.as_deref(),
+    );
 
     Json(GetAddressesResponse {
         distinct_accounts: result.distinct,
@@ -108,6 +121,17 @@ struct GetAuthorityAddressesResponse {
     authority: Pubkey,
     #[serde_as(as = "Vec<DisplayFromStr>")]
     addresses: Vec<Pubkey>,
+    tables: Vec<AuthorityTable>,
+}
+
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+struct AuthorityTable {
+    #[serde_as(as = "DisplayFromStr")]
+    address: Pubkey,
+    discriminator: u64,
+    label: String,
+    metadata: Vec<u8>,
 }
 
 #[serde_as]
@@ -116,6 +140,16 @@ struct GetLookupAddressInput {
     instructions: Vec<InstructionSmall>,
     #[serde_as(as = "Vec<DisplayFromStr>")]
     authorities: Vec<Pubkey>,
+    /// The name of the registries of the authorities, if not their default registries
+    #[serde(default)]
+    name: Option<String>,
+}
+
+/// The query of requests for the registry of an authority
+#[derive(Deserialize, Debug)]
+struct RegistryQuery {
+    /// The name of the registry, if not the authority's default registry
+    name: Option<String>,
 }
 
 #[derive(Clone)]